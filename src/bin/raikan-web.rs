@@ -177,6 +177,16 @@ struct InitMessage {
     paused: bool,
     pause_player_index: i8,
     pause_queued: bool,
+    #[serde(default)]
+    options: InitOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct InitOptions {
+    variant_name: String,
+    #[serde(flatten)]
+    game: game::GameOptions,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -601,6 +611,20 @@ impl HanabClient {
 #[derive(Debug)]
 struct Slot {
     index: u8,
+    card: Option<game::Card>,
+    clued: bool,
+    clues: Vec<(game::Clue, bool)>,
+}
+
+impl Slot {
+    fn new(index: u8, card: Option<game::Card>) -> Self {
+        Self {
+            index,
+            card,
+            clued: false,
+            clues: Vec::new(),
+        }
+    }
 }
 
 struct HanabGame {
@@ -609,8 +633,13 @@ struct HanabGame {
     hands: Vec<VecDeque<Slot>>,
     player: hyphenated::HyphenatedPlayer,
     variant: Variant,
+    options: game::GameOptions,
     current_player_index: Option<u8>,
     status: game::GameStatus,
+    played: Vec<u8>,
+    play_orders: Vec<game::PlayOrder>,
+    discarded: BTreeMap<game::Card, u8>,
+    drawn_cards: usize,
 }
 
 impl HanabGame {
@@ -620,24 +649,45 @@ impl HanabGame {
             hands.push(VecDeque::new());
         }
 
+        let variant = Variant::from_name(&init.options.variant_name).unwrap_or_else(|| {
+            eprintln!(
+                "Unsupported variant {:?}; falling back to No Variant",
+                init.options.variant_name
+            );
+            Variant::no_variant()
+        });
         let mut player = hyphenated::HyphenatedPlayer::new(true);
-        player.init(init.player_names.len() as u8, init.our_player_index);
+        player.init(
+            init.player_names.len() as u8,
+            init.our_player_index,
+            variant,
+            init.options.game,
+        );
 
         Self {
             player_names: init.player_names.clone(),
             hands,
             own_player: init.our_player_index,
             player,
-            variant: Variant {},
+            variant,
+            options: init.options.game,
             current_player_index: Some(0),
             status: game::GameStatus {
                 turn: 0,
                 score: 0,
-                max_score: 25,
+                max_score: variant.max_score(),
                 num_strikes: 0,
                 clues: 8,
                 blind_plays: 0,
             },
+            played: vec![0; variant.len()],
+            play_orders: variant
+                .suits()
+                .iter()
+                .map(|suit| variant.play_order(suit))
+                .collect(),
+            discarded: BTreeMap::new(),
+            drawn_cards: 0,
         }
     }
 
@@ -648,17 +698,30 @@ impl HanabGame {
         }
 
         self.player = hyphenated::HyphenatedPlayer::new(true);
-        self.player
-            .init(self.player_names.len() as u8, self.own_player);
+        self.player.init(
+            self.player_names.len() as u8,
+            self.own_player,
+            self.variant,
+            self.options,
+        );
         self.current_player_index = Some(0);
         self.status = game::GameStatus {
             turn: 0,
             score: 0,
-            max_score: 25,
+            max_score: self.variant.max_score(),
             num_strikes: 0,
             clues: 8,
             blind_plays: 0,
         };
+        self.played = vec![0; self.variant.len()];
+        self.play_orders = self
+            .variant
+            .suits()
+            .iter()
+            .map(|suit| self.variant.play_order(suit))
+            .collect();
+        self.discarded = BTreeMap::new();
+        self.drawn_cards = 0;
     }
 
     fn resolve_index(&self, player: u8) -> u8 {
@@ -671,7 +734,29 @@ impl HanabGame {
             return None;
         }
         self.current_player_index = None;
-        let action = self.player.act(&self.status);
+        let num_players = self.player_names.len();
+        let view = game::PlayerView {
+            status: &self.status,
+            played: &self.played,
+            play_orders: &self.play_orders,
+            discarded: &self.discarded,
+            deck_size: self.variant.deck_size().saturating_sub(self.drawn_cards),
+            active_player: self.own_player,
+            hands: (0..num_players)
+                .map(|player| {
+                    self.hands[(self.own_player as usize + player) % num_players]
+                        .iter()
+                        .map(|slot| game::VisibleCard {
+                            card: slot.card,
+                            order: slot.index,
+                            clued: slot.clued,
+                            clues: &slot.clues,
+                        })
+                        .collect()
+                })
+                .collect(),
+        };
+        let action = self.player.act(&view);
         println!("Decision: {:?}", action);
         Some(match action {
             game::Move::Play(pos) => ActionMessage {
@@ -691,13 +776,7 @@ impl HanabGame {
                     table_id,
                     action: 2,
                     target: (self.own_player + player) % self.player_names.len() as u8,
-                    value: Some(
-                        self.variant
-                            .suits()
-                            .iter()
-                            .position(|&s| s == color.suit())
-                            .unwrap() as u8,
-                    ),
+                    value: Some(self.variant.clue_color_index(&color) as u8),
                 },
                 game::Clue::Rank(rank) => ActionMessage {
                     table_id,
@@ -706,6 +785,12 @@ impl HanabGame {
                     value: Some(rank),
                 },
             },
+            game::Move::Resign() => ActionMessage {
+                table_id,
+                action: 4,
+                target: self.own_player,
+                value: Some(game::END_TERMINATED),
+            },
         })
     }
 
@@ -718,8 +803,9 @@ impl HanabGame {
                 suit_index,
                 rank,
             } => {
+                self.drawn_cards += 1;
                 if *player_index == self.own_player {
-                    self.hands[*player_index as usize].push_front(Slot { index: *order });
+                    self.hands[*player_index as usize].push_front(Slot::new(*order, None));
                     self.player.own_drawn();
                 } else if *suit_index < 0 || *rank < 0 {
                     eprintln!(
@@ -731,7 +817,7 @@ impl HanabGame {
                         suit: self.variant.suits()[*suit_index as usize],
                         rank: *rank as u8,
                     };
-                    self.hands[*player_index as usize].push_front(Slot { index: *order });
+                    self.hands[*player_index as usize].push_front(Slot::new(*order, Some(card)));
                     self.player
                         .drawn(self.resolve_index(*player_index) as usize, card);
                 }
@@ -760,6 +846,9 @@ impl HanabGame {
                         suit: self.variant.suits()[*suit_index as usize],
                         rank: *rank,
                     };
+                    let suit_index = *suit_index as usize;
+                    self.play_orders[suit_index] = self.play_orders[suit_index].started_with(*rank);
+                    self.played[suit_index] += 1;
                     self.player.played(
                         self.resolve_index(*player_index) as usize,
                         slot_pos,
@@ -790,6 +879,7 @@ impl HanabGame {
                         suit: self.variant.suits()[*suit_index as usize],
                         rank: *rank,
                     };
+                    *self.discarded.entry(card).or_insert(0) += 1;
                     if *failed {
                         self.player.played(
                             self.resolve_index(*player_index) as usize,
@@ -840,7 +930,11 @@ impl HanabGame {
                     );
                     game::Clue::Rank(clue_message.value)
                 } else {
-                    let clue_color = self.variant.suits()[clue_message.value as usize].clue_color();
+                    let clue_color = self
+                        .variant
+                        .clue_colors()
+                        .nth(clue_message.value as usize)
+                        .expect("clue color must exist in variant");
                     println!(
                         "Player {} clued to {} {:?} touching {} cards",
                         self.player_names[*giver as usize],
@@ -848,10 +942,13 @@ impl HanabGame {
                         clue_color,
                         touched.len(),
                     );
-                    game::Clue::Color(
-                        self.variant.suits()[clue_message.value as usize].clue_color(),
-                    )
+                    game::Clue::Color(clue_color)
                 };
+                for slot in self.hands[*target as usize].iter_mut() {
+                    let touched = list.contains(&slot.index);
+                    slot.clued |= touched;
+                    slot.clues.push((clue, touched));
+                }
                 self.player.clued(
                     self.resolve_index(*giver) as usize,
                     self.resolve_index(*target) as usize,
@@ -1,33 +1,399 @@
 use crate::game;
 use colored::*;
 
+/// Variants that can be looked up by their hanab.live name
+const VARIANTS: &[(&str, &[game::Suit])] = &[
+    (
+        "No Variant",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+        ],
+    ),
+    (
+        "6 Suits",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Teal(),
+        ],
+    ),
+    (
+        "Rainbow (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Rainbow(),
+        ],
+    ),
+    (
+        "Rainbow (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Rainbow(),
+        ],
+    ),
+    (
+        "Pink (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Pink(),
+        ],
+    ),
+    (
+        "Pink (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Pink(),
+        ],
+    ),
+    (
+        "Brown (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Brown(),
+        ],
+    ),
+    (
+        "Brown (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Brown(),
+        ],
+    ),
+    (
+        "Black (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Black(),
+        ],
+    ),
+    (
+        "Black (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Black(),
+        ],
+    ),
+    (
+        "Dark Rainbow (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::DarkRainbow(),
+        ],
+    ),
+    (
+        "Dark Rainbow (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::DarkRainbow(),
+        ],
+    ),
+    (
+        "Dark Pink (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::DarkPink(),
+        ],
+    ),
+    (
+        "Dark Pink (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::DarkPink(),
+        ],
+    ),
+    (
+        "Dark Brown (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::DarkBrown(),
+        ],
+    ),
+    (
+        "Dark Brown (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::DarkBrown(),
+        ],
+    ),
+    (
+        "Reversed (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Teal(),
+        ],
+    ),
+    (
+        "Reversed (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+        ],
+    ),
+    (
+        "Up or Down (6 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+            game::Suit::Teal(),
+        ],
+    ),
+    (
+        "Up or Down (5 Suits)",
+        &[
+            game::Suit::Red(),
+            game::Suit::Yellow(),
+            game::Suit::Green(),
+            game::Suit::Blue(),
+            game::Suit::Purple(),
+        ],
+    ),
+];
+
+/// Variants in which the last suit is played in reversed order (5 → 1)
+const REVERSED_VARIANTS: &[&str] = &["Reversed (6 Suits)", "Reversed (5 Suits)"];
+
+/// Variants in which every suit is played either up (1 → 5) or down (5 → 1);
+/// the START cards of hanab.live are not modelled: a suit starts with its 1 or its 5
+const UP_OR_DOWN_VARIANTS: &[&str] = &["Up or Down (6 Suits)", "Up or Down (5 Suits)"];
+
+/// hanab.live variant ids (as used in replay URLs); only ids verified against
+/// hanab.live are listed
+const HANABI_LIVE_IDS: &[(&str, u16)] = &[("No Variant", 0), ("6 Suits", 1)];
+
+/// Description of a (hanab.live) variant: its name and the ordered list of suits.
+///
+/// Card counts and clue-touch rules are properties of the individual suits;
+/// the variant is the single place every other component asks for them.
+/// At most six suits are supported (the size of the `CardQuantum` bit arrays).
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
-pub struct Variant {}
+pub struct Variant {
+    name: &'static str,
+    num_suits: u8,
+    suits: [game::Suit; 6],
+    /// bit mask of suit indices played from 5 down to 1
+    reversed: u8,
+    up_or_down: bool,
+}
 
 #[allow(clippy::len_without_is_empty)]
 impl Variant {
+    pub fn new(name: &'static str, suits: &[game::Suit]) -> Self {
+        assert!(
+            !suits.is_empty() && suits.len() <= 6,
+            "Variants need between one and six suits ({} given)",
+            suits.len()
+        );
+        let mut all_suits = [suits[0]; 6];
+        all_suits[..suits.len()].copy_from_slice(suits);
+        Self {
+            name,
+            num_suits: suits.len() as u8,
+            suits: all_suits,
+            reversed: 0,
+            up_or_down: false,
+        }
+    }
+
+    /// Plays the suit with the given index from 5 down to 1
+    pub fn with_reversed_suit(mut self, suit_index: usize) -> Self {
+        assert!(suit_index < self.len(), "reversed suit must exist");
+        self.reversed |= 1 << suit_index;
+        self
+    }
+
+    /// Lets each suit be played either from 1 up to 5 or from 5 down to 1
+    pub fn with_up_or_down(mut self) -> Self {
+        self.up_or_down = true;
+        self
+    }
+
+    pub fn no_variant() -> Self {
+        Self::new(VARIANTS[0].0, VARIANTS[0].1)
+    }
+
+    /// Looks up a variant by its hanab.live name
+    ///
+    /// ```
+    /// use raikan::Variant;
+    /// assert_eq!(Variant::from_name("No Variant"), Some(Variant::no_variant()));
+    /// assert_eq!(Variant::from_name("Unknown"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        VARIANTS
+            .iter()
+            .find(|(variant_name, _suits)| *variant_name == name)
+            .map(|(variant_name, suits)| {
+                let mut variant = Self::new(variant_name, suits);
+                if REVERSED_VARIANTS.contains(variant_name) {
+                    variant = variant.with_reversed_suit(suits.len() - 1);
+                }
+                if UP_OR_DOWN_VARIANTS.contains(variant_name) {
+                    variant = variant.with_up_or_down();
+                }
+                variant
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Looks up a variant by its hanab.live id
+    pub fn from_hanabi_live_id(id: u16) -> Option<Self> {
+        HANABI_LIVE_IDS
+            .iter()
+            .find(|(_name, variant_id)| *variant_id == id)
+            .and_then(|(name, _id)| Self::from_name(name))
+    }
+
+    /// hanab.live id of this variant, if known
+    pub fn hanabi_live_id(&self) -> Option<u16> {
+        HANABI_LIVE_IDS
+            .iter()
+            .find(|(name, _id)| *name == self.name)
+            .map(|(_name, id)| *id)
+    }
+
     pub fn len(&self) -> usize {
-        5
+        self.num_suits as usize
     }
 
-    pub fn suits(&self) -> [game::Suit; 5] {
-        [
-            game::Suit::Red(),
-            game::Suit::Yellow(),
-            game::Suit::Green(),
-            game::Suit::Blue(),
-            game::Suit::Purple(),
-        ]
+    pub fn suits(&self) -> &[game::Suit] {
+        &self.suits[..self.num_suits as usize]
     }
 
     pub fn suit_index(&self, suit: &game::Suit) -> usize {
-        let suits = self.suits();
-        for (index, _suit) in suits.iter().enumerate() {
-            if suits[index] == *suit {
-                return index;
-            }
+        self.suits()
+            .iter()
+            .position(|current| current == suit)
+            .unwrap_or(0)
+    }
+
+    pub fn max_score(&self) -> u8 {
+        5 * self.num_suits
+    }
+
+    /// Order in which the cards of a suit are played at the start of a game
+    pub fn play_order(&self, suit: &game::Suit) -> game::PlayOrder {
+        if self.up_or_down {
+            game::PlayOrder::Open()
+        } else if self.reversed & (1 << self.suit_index(suit)) > 0 {
+            game::PlayOrder::Down()
+        } else {
+            game::PlayOrder::Up()
         }
-        0
+    }
+
+    /// Number of copies of a card in the deck
+    pub fn card_count(&self, card: &game::Card) -> u8 {
+        if self.up_or_down && (card.rank == 1 || card.rank == 5) {
+            return 1;
+        }
+        match self.play_order(&card.suit) {
+            // the first played card is the unique one:
+            game::PlayOrder::Down() => card.suit.card_count(6 - card.rank),
+            _ => card.suit.card_count(card.rank),
+        }
+    }
+
+    /// Total number of cards in the deck
+    pub fn deck_size(&self) -> usize {
+        self.suits()
+            .iter()
+            .map(|suit| {
+                (1..=5)
+                    .map(|rank| self.card_count(&game::Card { suit: *suit, rank }) as usize)
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Whether a clue touches the given card
+    pub fn touches(&self, card: &game::Card, clue: game::Clue) -> bool {
+        card.affected(clue)
+    }
+
+    /// The color clues that can be given in this variant (in hanab.live order)
+    pub fn clue_colors(&self) -> impl Iterator<Item = game::ClueColor> + '_ {
+        self.suits().iter().filter_map(|suit| suit.clue_color())
+    }
+
+    pub fn clue_color_index(&self, color: &game::ClueColor) -> usize {
+        self.clue_colors()
+            .position(|current| current == *color)
+            .expect("clue color must exist in variant")
+    }
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Self::no_variant()
     }
 }
 
@@ -90,40 +456,33 @@ impl CardQuantum {
         self.soft_size = 0;
     }
 
-    pub fn limit_by_suit(&mut self, suit: &game::Suit, effect: bool) {
-        let index = self.variant.suit_index(suit);
-        if effect {
-            self.soft_size = self.soft_cards[index].count_ones() as u8;
-        } else {
-            self.soft_size -= self.soft_cards[index].count_ones() as u8;
+    /// Keeps (`effect`) or removes all cards a clue touches. A clue can touch
+    /// cards of multiple suits (e.g. rainbow or pink).
+    pub fn limit_by_clue(&mut self, clue: game::Clue, effect: bool) {
+        for (index, suit) in self.variant.suits().iter().enumerate() {
+            let touched = suit.clue_mask(clue);
+            let mask = if effect { touched } else { !touched };
+            self.hard_cards[index] &= mask;
+            self.soft_cards[index] &= mask;
         }
-        for i in 0..self.variant.len() {
-            if (i != index) == effect {
-                self.hard_cards[i] = 0;
-                self.soft_cards[i] = 0;
-            }
+        self.update_soft_count();
+    }
+
+    pub fn limit_by_color(&mut self, color: &game::ClueColor, effect: bool) {
+        self.limit_by_clue(game::Clue::Color(*color), effect);
+    }
+
+    /// Keeps or removes the cards touched by the color clue of the suit
+    /// (suits without an own color, like rainbow, leave the quantum unchanged)
+    #[deprecated(note = "a color clue can touch multiple suits, use `limit_by_color`")]
+    pub fn limit_by_suit(&mut self, suit: &game::Suit, effect: bool) {
+        if let Some(color) = suit.clue_color() {
+            self.limit_by_color(&color, effect);
         }
     }
 
     pub fn limit_by_rank(&mut self, rank: usize, effect: bool) {
-        let rank_bit = 1 << (rank - 1);
-        let rank_modifier = if effect {
-            self.soft_size = 0;
-            rank_bit
-        } else {
-            !rank_bit
-        };
-        for i in 0..self.variant.len() {
-            self.hard_cards[i] &= rank_modifier;
-            if self.soft_cards[i] & rank_bit > 0 {
-                if effect {
-                    self.soft_size += 1;
-                } else {
-                    self.soft_size -= 1;
-                }
-            }
-            self.soft_cards[i] &= rank_modifier;
-        }
+        self.limit_by_clue(game::Clue::Rank(rank as u8), effect);
     }
 
     pub fn add_card(&mut self, card: &game::Card, soft: bool) {
@@ -287,9 +646,100 @@ impl<'a> Iterator for CardIterator<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn no_variant_description() {
+        let variant = Variant::no_variant();
+        assert_eq!(variant.name(), "No Variant");
+        assert_eq!(variant.len(), 5);
+        assert_eq!(variant.max_score(), 25);
+        assert_eq!(variant.deck_size(), 50);
+        assert_eq!(variant.suit_index(&game::Suit::Blue()), 3);
+        assert_eq!(variant.clue_colors().count(), 5);
+    }
+
+    #[test]
+    fn six_suits_description() {
+        let variant = Variant::from_name("6 Suits").expect("known variant");
+        assert_eq!(variant.len(), 6);
+        assert_eq!(variant.max_score(), 30);
+        assert_eq!(variant.deck_size(), 60);
+        assert_eq!(variant.suit_index(&game::Suit::Teal()), 5);
+        assert_eq!(variant.clue_color_index(&game::ClueColor::Teal()), 5);
+        assert_eq!(variant.hanabi_live_id(), Some(1));
+        assert_eq!(Variant::from_hanabi_live_id(1), Some(variant));
+        let black = Variant::from_name("Black (6 Suits)").expect("known variant");
+        assert_eq!(black.deck_size(), 55);
+        assert_eq!(black.hanabi_live_id(), None);
+    }
+
+    #[test]
+    fn reversed_and_up_or_down_card_counts() {
+        let purple_5 = game::Card {
+            suit: game::Suit::Purple(),
+            rank: 5,
+        };
+        let reversed = Variant::from_name("Reversed (5 Suits)").expect("known variant");
+        assert_eq!(
+            reversed.play_order(&game::Suit::Red()),
+            game::PlayOrder::Up()
+        );
+        assert_eq!(
+            reversed.play_order(&game::Suit::Purple()),
+            game::PlayOrder::Down()
+        );
+        assert_eq!(reversed.card_count(&purple_5), 3);
+        assert_eq!(reversed.deck_size(), 50);
+        let up_or_down = Variant::from_name("Up or Down (5 Suits)").expect("known variant");
+        assert_eq!(
+            up_or_down.play_order(&game::Suit::Red()),
+            game::PlayOrder::Open()
+        );
+        assert_eq!(up_or_down.card_count(&purple_5), 1);
+        assert_eq!(up_or_down.deck_size(), 40);
+    }
+
+    #[test]
+    fn limit_by_clue_with_multi_suit_touches() {
+        let variant = Variant::from_name("Rainbow (5 Suits)").expect("known variant");
+        let rainbow2 = game::Card {
+            rank: 2,
+            suit: game::Suit::Rainbow(),
+        };
+        let red2 = game::Card {
+            rank: 2,
+            suit: game::Suit::Red(),
+        };
+        let mut touched = CardQuantum::new(variant);
+        touched.limit_by_color(&game::ClueColor::Red(), true);
+        assert_eq!(touched.size(), 10);
+        assert!(touched.contains(&red2));
+        assert!(touched.contains(&rainbow2));
+        let mut untouched = CardQuantum::new(variant);
+        untouched.limit_by_color(&game::ClueColor::Red(), false);
+        assert_eq!(untouched.size(), 15);
+        assert!(!untouched.contains(&red2));
+        assert!(!untouched.contains(&rainbow2));
+
+        let variant = Variant::from_name("Pink (5 Suits)").expect("known variant");
+        let mut touched = CardQuantum::new(variant);
+        touched.limit_by_rank(3, true);
+        assert_eq!(touched.size(), 4 + 5);
+        let mut untouched = CardQuantum::new(variant);
+        untouched.limit_by_rank(3, false);
+        assert_eq!(untouched.size(), 4 * 4);
+
+        let variant = Variant::from_name("Brown (5 Suits)").expect("known variant");
+        let mut touched = CardQuantum::new(variant);
+        touched.limit_by_rank(3, true);
+        assert_eq!(touched.size(), 4);
+        let mut untouched = CardQuantum::new(variant);
+        untouched.limit_by_rank(3, false);
+        assert_eq!(untouched.size(), 4 * 4 + 5);
+    }
+
     #[test]
     fn it_initials_with_everything() {
-        let variant = Variant {};
+        let variant = Variant::no_variant();
         let c = CardQuantum::new(variant);
         assert_eq!(c.soft_cards[0], 0b11111);
         assert_eq!(c.soft_cards[1], 0b11111);
@@ -305,7 +755,7 @@ mod tests {
 
     #[test]
     fn it_clears() {
-        let variant = Variant {};
+        let variant = Variant::no_variant();
         let mut c = CardQuantum::new(variant);
         assert_eq!(c.soft_cards[0], 0b11111);
         assert_eq!(c.soft_cards[1], 0b11111);
@@ -332,7 +782,7 @@ mod tests {
 
     #[test]
     fn contains() {
-        let variant = Variant {};
+        let variant = Variant::no_variant();
         let mut c = CardQuantum::new(variant);
         let card1 = game::Card {
             rank: 1,
@@ -353,7 +803,7 @@ mod tests {
 
     #[test]
     fn size() {
-        let variant = Variant {};
+        let variant = Variant::no_variant();
         let mut c = CardQuantum::new(variant);
         assert_eq!(c.size(), 25);
         c.clear();
@@ -1,11 +1,12 @@
+use crate::card_quantum::Variant;
 use crate::game;
 
 use rand::thread_rng;
 use rand::{Rng, seq::SliceRandom};
 
-pub struct DiscardPlayer {
-    num_hand_cards: u8,
-}
+/// Always discards the oldest card (and clues when discarding is not allowed)
+#[derive(Clone, Default)]
+pub struct DiscardPlayer;
 
 impl std::fmt::Debug for DiscardPlayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -14,34 +15,40 @@ impl std::fmt::Debug for DiscardPlayer {
 }
 
 impl game::PlayerStrategy for DiscardPlayer {
-    fn init(&mut self, _num_players: u8, _own_index: u8) {}
-    fn act(&mut self, _status: &game::GameStatus) -> game::Move {
-        game::Move::Discard(self.num_hand_cards - 1)
+    fn name(&self) -> String {
+        "discard".to_string()
     }
 
-    fn drawn(&mut self, _player: usize, _card: game::Card) {}
-    fn own_drawn(&mut self) {
-        self.num_hand_cards -= 1;
+    fn init(
+        &mut self,
+        _num_players: u8,
+        _own_index: u8,
+        _variant: Variant,
+        _options: game::GameOptions,
+    ) {
     }
-
-    fn played(&mut self, player: usize, _pos: usize, _card: game::Card, _successful: bool) {
-        if player == 0 {
-            self.num_hand_cards -= 1;
+    fn act(&mut self, view: &game::PlayerView) -> game::Move {
+        if view.status.clues == 8
+            && let Some(card) = view.hands[1].first().and_then(|card| card.card)
+        {
+            return game::Move::Clue(1, game::Clue::Rank(card.rank));
         }
+        game::Move::Discard(view.hands[0].len() as u8 - 1)
     }
 
-    fn discarded(&mut self, player: usize, _pos: usize, _card: game::Card) {
-        if player == 0 {
-            self.num_hand_cards -= 1;
-        }
-    }
+    fn drawn(&mut self, _player: usize, _card: game::Card) {}
+    fn own_drawn(&mut self) {}
+
+    fn played(&mut self, _player: usize, _pos: usize, _card: game::Card, _successful: bool) {}
+
+    fn discarded(&mut self, _player: usize, _pos: usize, _card: game::Card) {}
     fn clued(&mut self, _who: usize, _whom: usize, _clue: game::Clue, _touched: game::PositionSet) {
     }
 }
 
-pub struct PlayPlayer {
-    num_hand_cards: u8,
-}
+/// Always plays the oldest card
+#[derive(Clone, Default)]
+pub struct PlayPlayer;
 
 impl std::fmt::Debug for PlayPlayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -50,31 +57,34 @@ impl std::fmt::Debug for PlayPlayer {
 }
 
 impl game::PlayerStrategy for PlayPlayer {
-    fn init(&mut self, _num_players: u8, _own_index: u8) {}
-
-    fn drawn(&mut self, _player: usize, _card: game::Card) {}
-    fn own_drawn(&mut self) {
-        self.num_hand_cards += 1;
-    }
-    fn played(&mut self, player: usize, _pos: usize, _card: game::Card, _successful: bool) {
-        if player == 0 {
-            self.num_hand_cards -= 1;
-        }
+    fn name(&self) -> String {
+        "play".to_string()
     }
 
-    fn discarded(&mut self, player: usize, _pos: usize, _card: game::Card) {
-        if player == 0 {
-            self.num_hand_cards -= 1;
-        }
+    fn init(
+        &mut self,
+        _num_players: u8,
+        _own_index: u8,
+        _variant: Variant,
+        _options: game::GameOptions,
+    ) {
     }
+
+    fn drawn(&mut self, _player: usize, _card: game::Card) {}
+    fn own_drawn(&mut self) {}
+    fn played(&mut self, _player: usize, _pos: usize, _card: game::Card, _successful: bool) {}
+
+    fn discarded(&mut self, _player: usize, _pos: usize, _card: game::Card) {}
     fn clued(&mut self, _who: usize, _whom: usize, _clue: game::Clue, _touched: game::PositionSet) {
     }
 
-    fn act(&mut self, _status: &game::GameStatus) -> game::Move {
-        game::Move::Play(self.num_hand_cards - 1)
+    fn act(&mut self, view: &game::PlayerView) -> game::Move {
+        game::Move::Play(view.hands[0].len() as u8 - 1)
     }
 }
 
+/// Clues a random card of another player (and discards without clue tokens)
+#[derive(Clone, Default)]
 pub struct RandCluePlayer;
 
 impl std::fmt::Debug for RandCluePlayer {
@@ -84,7 +94,18 @@ impl std::fmt::Debug for RandCluePlayer {
 }
 
 impl game::PlayerStrategy for RandCluePlayer {
-    fn init(&mut self, _num_players: u8, _own_index: u8) {}
+    fn name(&self) -> String {
+        "rand_clue".to_string()
+    }
+
+    fn init(
+        &mut self,
+        _num_players: u8,
+        _own_index: u8,
+        _variant: Variant,
+        _options: game::GameOptions,
+    ) {
+    }
     fn drawn(&mut self, _player: usize, _card: game::Card) {}
     fn own_drawn(&mut self) {}
 
@@ -94,22 +115,19 @@ impl game::PlayerStrategy for RandCluePlayer {
     fn clued(&mut self, _who: usize, _whom: usize, _clue: game::Clue, _touched: game::PositionSet) {
     }
 
-    fn act(&mut self, _status: &game::GameStatus) -> game::Move {
+    fn act(&mut self, view: &game::PlayerView) -> game::Move {
+        if view.status.clues == 0 {
+            return game::Move::Discard(view.hands[0].len() as u8 - 1);
+        }
         let mut rng = thread_rng();
-        if rng.gen_bool(0.3) {
-            game::Move::Clue(0, game::Clue::Rank(rng.gen_range(1..=5)))
-        } else {
-            let colors = [
-                game::ClueColor::Blue(),
-                game::ClueColor::Green(),
-                game::ClueColor::Yellow(),
-                game::ClueColor::Red(),
-                game::ClueColor::Purple(),
-            ];
-            game::Move::Clue(
-                0,
-                game::Clue::Color(*colors.choose(&mut rng).expect("asdf")),
-            )
+        let player = rng.gen_range(1..view.num_players());
+        let card = view.hands[player as usize]
+            .choose(&mut rng)
+            .and_then(|card| card.card)
+            .expect("other players hold visible cards");
+        match card.suit.clue_color() {
+            Some(color) if !rng.gen_bool(0.3) => game::Move::Clue(player, game::Clue::Color(color)),
+            _ => game::Move::Clue(player, game::Clue::Rank(card.rank)),
         }
     }
 }
@@ -1,8 +1,8 @@
 use std::collections::BTreeMap;
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
-use crate::game;
-
+use crate::card_quantum::Variant;
 pub use crate::position_set::PositionSet;
 
 use colored::*;
@@ -16,6 +16,21 @@ pub enum Suit {
     Yellow(),
     Blue(),
     Purple(),
+    Teal(),
+    /// touched by every color clue, has no color of its own
+    Rainbow(),
+    /// touched by every rank clue
+    Pink(),
+    /// touched by no rank clue
+    Brown(),
+    /// one-of-each ("dark") suit with its own color
+    Black(),
+    /// one-of-each version of rainbow
+    DarkRainbow(),
+    /// one-of-each version of pink
+    DarkPink(),
+    /// one-of-each version of brown
+    DarkBrown(),
 }
 
 impl Suit {
@@ -26,6 +41,31 @@ impl Suit {
             Self::Yellow() => Color::Yellow,
             Self::Blue() => Color::Cyan,
             Self::Purple() => Color::Magenta,
+            // cyan is taken by blue
+            Self::Teal() => Color::TrueColor {
+                r: 0,
+                g: 150,
+                b: 130,
+            },
+            Self::Rainbow() => Color::BrightWhite,
+            Self::Pink() => Color::BrightMagenta,
+            Self::Brown() => Color::TrueColor {
+                r: 165,
+                g: 100,
+                b: 40,
+            },
+            Self::Black() => Color::BrightBlack,
+            Self::DarkRainbow() => Color::White,
+            Self::DarkPink() => Color::TrueColor {
+                r: 200,
+                g: 60,
+                b: 140,
+            },
+            Self::DarkBrown() => Color::TrueColor {
+                r: 110,
+                g: 60,
+                b: 20,
+            },
         }
     }
 
@@ -36,10 +76,29 @@ impl Suit {
             Self::Yellow() => 'y',
             Self::Blue() => 'b',
             Self::Purple() => 'p',
+            Self::Teal() => 't',
+            Self::Rainbow() => 'm',
+            Self::Pink() => 'i',
+            Self::Brown() => 'n',
+            Self::Black() => 'k',
+            Self::DarkRainbow() => 'M',
+            Self::DarkPink() => 'I',
+            Self::DarkBrown() => 'N',
         }
     }
 
+    /// Dark suits contain every card only once
+    pub fn is_dark(&self) -> bool {
+        matches!(
+            self,
+            Self::Black() | Self::DarkRainbow() | Self::DarkPink() | Self::DarkBrown()
+        )
+    }
+
     pub fn card_count(&self, rank: u8) -> u8 {
+        if self.is_dark() {
+            return 1;
+        }
         match rank {
             1 => 3,
             5 => 1,
@@ -49,25 +108,41 @@ impl Suit {
 
     fn affected(&self, rank: u8, clue: Clue) -> bool {
         match clue {
-            Clue::Rank(clue_rank) => rank == clue_rank,
-            Clue::Color(clue_color) => matches!(
-                (self, clue_color),
-                (Self::Red(), ClueColor::Red())
-                    | (Self::Blue(), ClueColor::Blue())
-                    | (Self::Yellow(), ClueColor::Yellow())
-                    | (Self::Green(), ClueColor::Green())
-                    | (Self::Purple(), ClueColor::Purple()),
-            ),
+            Clue::Rank(clue_rank) => match self {
+                Self::Pink() | Self::DarkPink() => true,
+                Self::Brown() | Self::DarkBrown() => false,
+                _ => rank == clue_rank,
+            },
+            Clue::Color(clue_color) => match self {
+                Self::Rainbow() | Self::DarkRainbow() => true,
+                _ => self.clue_color() == Some(clue_color),
+            },
         }
     }
 
-    pub fn clue_color(&self) -> ClueColor {
+    /// Bit mask of the ranks (bit 0 = rank 1) of this suit that are touched by a clue
+    pub fn clue_mask(&self, clue: Clue) -> u8 {
+        (1..=5)
+            .filter(|rank| self.affected(*rank, clue))
+            .fold(0, |mask, rank| mask | (1 << (rank - 1)))
+    }
+
+    /// The color clue naming this suit (`None` for suits without an own color like rainbow)
+    pub fn clue_color(&self) -> Option<ClueColor> {
         match self {
-            Self::Red() => ClueColor::Red(),
-            Self::Blue() => ClueColor::Blue(),
-            Self::Yellow() => ClueColor::Yellow(),
-            Self::Green() => ClueColor::Green(),
-            Self::Purple() => ClueColor::Purple(),
+            Self::Red() => Some(ClueColor::Red()),
+            Self::Blue() => Some(ClueColor::Blue()),
+            Self::Yellow() => Some(ClueColor::Yellow()),
+            Self::Green() => Some(ClueColor::Green()),
+            Self::Purple() => Some(ClueColor::Purple()),
+            Self::Teal() => Some(ClueColor::Teal()),
+            Self::Rainbow() => None,
+            Self::Pink() => Some(ClueColor::Pink()),
+            Self::Brown() => Some(ClueColor::Brown()),
+            Self::Black() => Some(ClueColor::Black()),
+            Self::DarkRainbow() => None,
+            Self::DarkPink() => Some(ClueColor::Pink()),
+            Self::DarkBrown() => Some(ClueColor::Brown()),
         }
     }
 }
@@ -98,6 +173,65 @@ mod suit_tests {
             assert_eq!(suit.card_count(5), 1);
         }
     }
+
+    #[test]
+    fn special_suit_clues() {
+        let red = Clue::Color(ClueColor::Red());
+        let three = Clue::Rank(3);
+        assert!(Suit::Rainbow().affected(2, red));
+        assert!(Suit::Rainbow().affected(3, three));
+        assert!(!Suit::Rainbow().affected(2, three));
+        assert!(Suit::Pink().affected(2, three));
+        assert!(!Suit::Pink().affected(2, red));
+        assert!(Suit::Pink().affected(2, Clue::Color(ClueColor::Pink())));
+        assert!(!Suit::Brown().affected(3, three));
+        assert!(Suit::Brown().affected(3, Clue::Color(ClueColor::Brown())));
+        assert_eq!(Suit::Red().clue_mask(three), 0b00100);
+        assert_eq!(Suit::Pink().clue_mask(three), 0b11111);
+        assert_eq!(Suit::Brown().clue_mask(three), 0);
+        assert_eq!(Suit::Rainbow().clue_mask(red), 0b11111);
+    }
+
+    #[test]
+    fn play_orders() {
+        assert_eq!(PlayOrder::Up().step(2), Some(1));
+        assert_eq!(PlayOrder::Down().step(2), Some(3));
+        assert_eq!(PlayOrder::Down().rank(0), Some(5));
+        assert_eq!(PlayOrder::Down().rank(5), None);
+        assert_eq!(PlayOrder::Open().step(1), None);
+        assert_eq!(PlayOrder::Open().started_with(1), PlayOrder::Up());
+        assert_eq!(PlayOrder::Open().started_with(5), PlayOrder::Down());
+        assert_eq!(PlayOrder::Down().started_with(1), PlayOrder::Down());
+    }
+
+    #[test]
+    fn reversed_play_state() {
+        let variant = Variant::from_name("Reversed (5 Suits)").expect("known variant");
+        let mut game = Game::empty_with_options(4, variant, GameOptions::default());
+        let purple = |rank| Card {
+            suit: Suit::Purple(),
+            rank,
+        };
+        assert!(game.is_playable(&purple(5)));
+        assert!(!game.is_playable(&purple(1)));
+        assert_eq!(purple(1).play_state(&game), CardPlayState::Critical());
+        game.played[4] = 2;
+        assert_eq!(purple(4).play_state(&game), CardPlayState::Trash());
+        assert_eq!(purple(3).play_state(&game), CardPlayState::Playable());
+        game.discard(purple(2));
+        game.discard(purple(2));
+        assert_eq!(purple(1).play_state(&game), CardPlayState::Dead());
+        assert_eq!(game.status.max_score, 23);
+    }
+
+    #[test]
+    fn dark_card_counts() {
+        for suit in [Suit::Black(), Suit::DarkRainbow()].iter() {
+            for rank in 1..=5 {
+                assert_eq!(suit.card_count(rank), 1);
+            }
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -111,25 +245,85 @@ impl Card {
         self.suit.affected(self.rank, clue)
     }
     pub fn play_state(&self, game: &Game) -> CardPlayState {
-        if self.rank > game.max_rank_for_suit(self.suit) {
+        let suit_index = game.variant.suit_index(&self.suit);
+        let order = game.play_orders[suit_index];
+        if order
+            .step(self.rank)
+            .is_some_and(|step| step < game.played[suit_index])
+        {
+            return CardPlayState::Trash();
+        }
+        let reachable_in = |order: PlayOrder| {
+            order
+                .step(self.rank)
+                .is_some_and(|step| step < game.max_height(self.suit, order))
+        };
+        let reachable = match order {
+            PlayOrder::Open() => reachable_in(PlayOrder::Up()) || reachable_in(PlayOrder::Down()),
+            _ => reachable_in(order),
+        };
+        if !reachable {
             return CardPlayState::Dead();
         }
-        match self.rank as i8 - game.played_rank(&self.suit) as i8 {
-            diff if diff <= 0 => CardPlayState::Trash(),
-            1 => CardPlayState::Playable(),
-            _ => match self.suit.card_count(self.rank) - game.discarded.get(self).unwrap_or(&0) {
-                0 => CardPlayState::Dead(),
-                1 => CardPlayState::Critical(),
-                _ => CardPlayState::Normal(),
-            },
+        if game.is_playable(self) {
+            return CardPlayState::Playable();
+        }
+        match game.variant.card_count(self) - game.discarded.get(self).unwrap_or(&0) {
+            0 => CardPlayState::Dead(),
+            1 => CardPlayState::Critical(),
+            _ => CardPlayState::Normal(),
+        }
+    }
+}
+
+/// Order in which the cards of a suit are played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayOrder {
+    /// from 1 up to 5
+    Up(),
+    /// from 5 down to 1 (reversed suits)
+    Down(),
+    /// Up or Down suit without played cards: can be started with its 1 or its 5
+    Open(),
+}
+
+impl PlayOrder {
+    /// Position of a rank within the play order (`None` while the order is open)
+    pub fn step(&self, rank: u8) -> Option<u8> {
+        match self {
+            Self::Up() => Some(rank - 1),
+            Self::Down() => Some(5 - rank),
+            Self::Open() => None,
+        }
+    }
+
+    /// Rank at the given position of the play order (`None` past the last card)
+    pub fn rank(&self, step: u8) -> Option<u8> {
+        match self {
+            _ if step >= 5 => None,
+            Self::Up() => Some(step + 1),
+            Self::Down() => Some(5 - step),
+            Self::Open() => None,
+        }
+    }
+
+    /// The order after the given rank was played
+    pub fn started_with(&self, rank: u8) -> Self {
+        match self {
+            Self::Open() if rank == 5 => Self::Down(),
+            Self::Open() => Self::Up(),
+            _ => *self,
         }
     }
 }
 
+#[derive(Clone)]
 pub struct CardState {
     card: Card,
     clued: bool,
     index: u8,
+    /// clues given to the hand while holding the card and whether they touched it
+    clues: Vec<(Clue, bool)>,
 }
 
 impl CardState {
@@ -138,14 +332,37 @@ impl CardState {
             card,
             clued: false,
             index,
+            clues: Vec::new(),
         }
     }
 
-    fn clue(&mut self, clue: Clue) -> bool {
-        let clued = self.card.affected(clue);
+    fn clue(&mut self, variant: &Variant, clue: Clue) -> bool {
+        let clued = variant.touches(&self.card, clue);
         self.clued |= clued;
+        self.clues.push((clue, clued));
         clued
     }
+
+    fn visible(&self, own_hand: bool) -> VisibleCard<'_> {
+        VisibleCard {
+            card: (!own_hand).then_some(self.card),
+            order: self.index,
+            clued: self.clued,
+            clues: &self.clues,
+        }
+    }
+}
+
+/// A card in a hand as a player sees it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VisibleCard<'a> {
+    /// the identity of the card, unless it is in the own hand
+    pub card: Option<Card>,
+    /// position of the card in the deck (order of drawing)
+    pub order: u8,
+    pub clued: bool,
+    /// clues given to the hand while holding the card and whether they touched it
+    pub clues: &'a [(Clue, bool)],
 }
 
 impl std::fmt::Debug for CardState {
@@ -174,7 +391,10 @@ impl std::fmt::Debug for Card {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// hanab.live end condition of games the players stopped
+pub const END_TERMINATED: u8 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GameState {
     Early(),
     Mid(),
@@ -183,54 +403,830 @@ pub enum GameState {
     Won(),
     Finished(),
     Invalid(),
+    /// ended early (e.g. a player resigned), with the hanab.live end condition
+    Terminated(u8),
 }
 
-#[derive(Serialize, Deserialize)]
-struct HanabiLiveCard {
+impl GameState {
+    /// Whether no more moves can be made
+    pub fn is_over(&self) -> bool {
+        !matches!(self, Self::Early() | Self::Mid() | Self::Final(_))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Early() => "Early",
+            Self::Mid() => "Mid",
+            Self::Final(_) => "Final",
+            Self::Lost() => "Lost",
+            Self::Won() => "Won",
+            Self::Finished() => "Finished",
+            Self::Invalid() => "Invalid",
+            Self::Terminated(_) => "Terminated",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct HanabiLiveCard {
     #[serde(rename = "suitIndex")]
     suit_index: u8,
     rank: u8,
 }
 
-#[derive(Serialize, Deserialize)]
-struct HanabiLiveAction {
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct HanabiLiveAction {
+    /// 0: play, 1: discard, 2: colour clue, 3: rank clue, 4: game over
     #[serde(rename = "type")]
-    action: u8,
-    target: u8,
+    pub(crate) action: u8,
+    pub(crate) target: u8,
     #[serde(skip_serializing_if = "Option::is_none")]
-    value: Option<u8>,
+    pub(crate) value: Option<u8>,
+}
+
+/// Reasons why a game can not be set up, see [`GameBuilder::build`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetupError {
+    /// games need 2 to 6 players
+    InvalidPlayerCount(u8),
+    /// the deck has not enough cards for the starting hands
+    DeckTooSmall(),
+    /// the card is not part of the variant or used more often than it exists
+    TooManyCopies(Card),
+    /// the card can not be played on the stacks set up so far
+    NotPlayable(Card),
+    /// more than 8 clue tokens or already 3 strikes
+    InvalidTokens(),
+    /// the giver of a known clue does not exist
+    InvalidClueGiver(u8),
+    /// a known clue could not have been given
+    InvalidClue(MoveError),
+    /// the game option is not implemented by the simulator
+    UnsupportedOption(&'static str),
+}
+
+impl std::fmt::Display for SetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidPlayerCount(count) => write!(f, "{count} players can't play (2 to 6)"),
+            Self::DeckTooSmall() => f.write_str("the deck is too small for the starting hands"),
+            Self::TooManyCopies(card) => write!(f, "too many copies of {card:?}"),
+            Self::NotPlayable(card) => write!(f, "{card:?} can not be played on the stacks"),
+            Self::InvalidTokens() => f.write_str("invalid number of clue tokens or strikes"),
+            Self::InvalidClueGiver(giver) => write!(f, "known clue giver {giver} does not exist"),
+            Self::InvalidClue(error) => write!(f, "invalid known clue: {error}"),
+            Self::UnsupportedOption(name) => write!(f, "the {name} option is not supported"),
+        }
+    }
+}
+
+impl std::error::Error for SetupError {}
+
+/// Sets up a game from an explicit deck, optionally at a later position
+///
+/// The deck is given in draw order: the first cards are dealt to the players
+/// (starting with player 0), the remaining ones are drawn afterwards. Cards on
+/// the stacks or in the discard pile must not be part of the deck.
+///
+/// Games starting from a later position can not be exported as hanab.live replay.
+pub struct GameBuilder {
+    variant: Variant,
+    options: GameOptions,
+    deck: Vec<Card>,
+    played: Vec<Card>,
+    discarded: Vec<Card>,
+    clues: u8,
+    strikes: u8,
+    known_clues: Vec<(u8, u8, Clue)>,
+    observers: Vec<SharedObserver>,
+    debug: bool,
+}
+
+impl GameBuilder {
+    pub fn new(deck: impl IntoIterator<Item = Card>) -> Self {
+        Self {
+            variant: Variant::no_variant(),
+            options: GameOptions::default(),
+            deck: deck.into_iter().collect(),
+            played: Vec::new(),
+            discarded: Vec::new(),
+            clues: 8,
+            strikes: 0,
+            known_clues: Vec::new(),
+            observers: Vec::new(),
+            debug: false,
+        }
+    }
+
+    pub fn variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn options(mut self, options: GameOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Cards already on the stacks, in the order they were played
+    pub fn played(mut self, cards: impl IntoIterator<Item = Card>) -> Self {
+        self.played.extend(cards);
+        self
+    }
+
+    pub fn discarded(mut self, cards: impl IntoIterator<Item = Card>) -> Self {
+        self.discarded.extend(cards);
+        self
+    }
+
+    pub fn clues(mut self, clues: u8) -> Self {
+        self.clues = clues;
+        self
+    }
+
+    pub fn strikes(mut self, strikes: u8) -> Self {
+        self.strikes = strikes;
+        self
+    }
+
+    /// A clue the `giver` gave to the `receiver` (both absolute player indexes)
+    /// on the starting hands; it does not cost a clue token
+    pub fn known_clue(mut self, giver: u8, receiver: u8, clue: Clue) -> Self {
+        self.known_clues.push((giver, receiver, clue));
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Observes the game already while dealing the starting hands
+    pub fn observer(mut self, observer: SharedObserver) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    pub fn build(self, players: &mut [&mut dyn PlayerStrategy]) -> Result<Game, SetupError> {
+        let num_players = players.len() as u8;
+        if !(2..=6).contains(&num_players) {
+            return Err(SetupError::InvalidPlayerCount(num_players));
+        }
+        if let Some(option) = self.options.unsupported() {
+            return Err(SetupError::UnsupportedOption(option));
+        }
+        let mut counts = BTreeMap::new();
+        for card in self.deck.iter().chain(&self.played).chain(&self.discarded) {
+            let count = counts.entry(*card).and_modify(|e| *e += 1).or_insert(1);
+            if !self.variant.suits().contains(&card.suit)
+                || !(1..=5).contains(&card.rank)
+                || *count > self.variant.card_count(card)
+            {
+                return Err(SetupError::TooManyCopies(*card));
+            }
+        }
+        if self.deck.len() < (num_players * self.options.hand_size(num_players)) as usize {
+            return Err(SetupError::DeckTooSmall());
+        }
+        if self.clues > 8 || self.strikes >= 3 {
+            return Err(SetupError::InvalidTokens());
+        }
+
+        let mut game = Game::empty_with_options(num_players, self.variant, self.options);
+        game.set_strategy_names(players);
+        game.custom_position = !self.played.is_empty()
+            || !self.discarded.is_empty()
+            || self.clues != 8
+            || self.strikes != 0
+            || !self.known_clues.is_empty();
+        for (pos, strategy) in players.iter_mut().enumerate() {
+            strategy.init(num_players, pos as u8, game.variant, game.options);
+        }
+        for card in self.played {
+            if !game.is_playable(&card) {
+                return Err(SetupError::NotPlayable(card));
+            }
+            game.add_to_stack(card);
+            game.remove_for_setup(card, true, players);
+        }
+        for card in self.discarded {
+            game.discard(card);
+            game.remove_for_setup(card, false, players);
+            game.state = GameState::Mid();
+        }
+        game.deck = self.deck.into();
+        game.observers = Observers(self.observers);
+        if self.debug {
+            game.add_observer(GameLogger::shared());
+        }
+        game.deal(players);
+
+        for (giver, receiver, clue) in self.known_clues {
+            if giver >= num_players {
+                return Err(SetupError::InvalidClueGiver(giver));
+            }
+            if receiver >= num_players || giver == receiver {
+                return Err(SetupError::InvalidClue(MoveError::InvalidClueReceiver(
+                    receiver,
+                )));
+            }
+            let player = (num_players + receiver - giver) % num_players;
+            game.validate_clue(giver as usize, player, clue)
+                .map_err(SetupError::InvalidClue)?;
+            let mut touched = PositionSet::new(game.hands[receiver as usize].len() as u8);
+            for (pos, card_state) in game.hands[receiver as usize].iter_mut().enumerate() {
+                if card_state.clue(&game.variant, clue) {
+                    touched.add(pos as u8);
+                }
+            }
+            for (notify_player, strategy) in players.iter_mut().enumerate() {
+                strategy.clued(
+                    game.relative_player_index(giver as usize, notify_player),
+                    game.relative_player_index(receiver as usize, notify_player),
+                    clue,
+                    touched,
+                );
+            }
+        }
+        game.status.clues = self.clues;
+        game.status.num_strikes = self.strikes;
+        if game.status.score == game.variant.max_score() {
+            game.state = GameState::Won();
+        }
+        Ok(game)
+    }
+}
+
+/// Reasons why a hanab.live replay can not be loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// the replay is not valid JSON in the hanab.live format
+    InvalidJson(String),
+    /// the replay URL is malformed, with a description of the problem
+    InvalidUrl(String),
+    UnknownVariant(String),
+    UnknownVariantId(u16),
+    /// the option flags of a replay URL are unknown
+    UnknownOptions(String),
+    /// the replay is for a different number of players than strategies are given
+    PlayerCount(usize),
+    /// the deck card at this index does not exist in the variant
+    InvalidCard(usize),
+    InvalidSetup(SetupError),
+    /// the action at this index is unknown or refers to a card not in the hand
+    InvalidAction(usize),
+    /// the move at this action index is not allowed
+    InvalidMove(usize, MoveError),
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidJson(error) => write!(f, "invalid replay JSON: {error}"),
+            Self::InvalidUrl(message) => write!(f, "invalid replay URL: {message}"),
+            Self::UnknownVariant(name) => write!(f, "unknown variant {name:?}"),
+            Self::UnknownVariantId(id) => write!(f, "unknown variant id {id}"),
+            Self::UnknownOptions(flags) => write!(f, "unknown option flags {flags:?}"),
+            Self::PlayerCount(count) => write!(f, "replay is for {count} players"),
+            Self::InvalidCard(index) => write!(f, "invalid deck card {index}"),
+            Self::InvalidSetup(error) => write!(f, "{error}"),
+            Self::InvalidAction(index) => write!(f, "invalid action {index}"),
+            Self::InvalidMove(index, error) => write!(f, "invalid move at action {index}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Full engine state of a game at one turn, see [`Game::snapshot`]
+#[derive(Clone)]
+pub struct GameSnapshot(Game);
+
+impl GameSnapshot {
+    pub fn turn(&self) -> u8 {
+        self.0.status.turn
+    }
+
+    /// Creates an independent game continuing from this snapshot
+    pub fn branch(&self) -> Game {
+        self.0.clone()
+    }
+}
+
+/// Something that happened in a game, see [`GameObserver`]
+///
+/// Players are given as absolute indexes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Drawn {
+        player: usize,
+        card: Card,
+    },
+    Played {
+        player: usize,
+        pos: u8,
+        card: Card,
+        /// the card was not clued
+        blind: bool,
+    },
+    Misplayed {
+        player: usize,
+        pos: u8,
+        card: Card,
+    },
+    Discarded {
+        player: usize,
+        pos: u8,
+        card: Card,
+    },
+    Clued {
+        giver: usize,
+        receiver: usize,
+        clue: Clue,
+        touched: PositionSet,
+    },
+    Strike {
+        num_strikes: u8,
+    },
+    StateChanged {
+        from: GameState,
+        to: GameState,
+    },
+    GameOver {
+        state: GameState,
+    },
+    /// the move was not allowed, the game becomes invalid
+    InvalidMove {
+        player: usize,
+        action: Move,
+        error: MoveError,
+    },
+}
+
+/// Receives the events of a game, e.g. to log or collect statistics
+pub trait GameObserver {
+    fn event(&mut self, status: &GameStatus, event: &GameEvent);
+
+    /// Called before [`Game::run`] and after every [`Game::step`] with the whole table
+    fn position(&mut self, _game: &Game, _strategies: &[&mut dyn PlayerStrategy]) {}
+}
+
+/// Observer shared between the game and its owner, see [`Game::add_observer`]
+pub type SharedObserver = Arc<Mutex<dyn GameObserver + Send>>;
+
+/// Observers of a game; clones (snapshots and branches) start without any
+#[derive(Default)]
+struct Observers(Vec<SharedObserver>);
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// Prints the moves and the table after every step to stdout (attached to games set up with `debug`)
+pub struct GameLogger;
+
+impl GameLogger {
+    pub fn shared() -> SharedObserver {
+        Arc::new(Mutex::new(Self))
+    }
+}
+
+impl GameObserver for GameLogger {
+    fn event(&mut self, status: &GameStatus, event: &GameEvent) {
+        match event {
+            GameEvent::Played {
+                player,
+                pos,
+                card,
+                blind: false,
+            } => println!("Player {player} played successfully {card:?} from pos {pos}"),
+            GameEvent::Played {
+                player,
+                pos,
+                card,
+                blind: true,
+            } => {
+                println!("Player {player} played successfully blind-played {card:?} from pos {pos}")
+            }
+            GameEvent::Misplayed { player, pos, card } => {
+                println!("Player {player} failed to play {card:?} from pos {pos}")
+            }
+            GameEvent::Discarded { player, pos, card } => {
+                println!("Player {player} discarded {card:?} from pos {pos}")
+            }
+            GameEvent::Clued {
+                giver,
+                receiver,
+                clue,
+                touched,
+            } => println!(
+                "Player {giver} clue played {receiver} about {} {clue:?} cards",
+                touched.len()
+            ),
+            GameEvent::GameOver {
+                state: GameState::Lost(),
+            } => println!("Game lost due to three strikes"),
+            GameEvent::InvalidMove {
+                player,
+                action,
+                error,
+            } => println!(
+                "Invalid move {action:?} of player {player}: {error}; turn {}",
+                status.turn
+            ),
+            _ => {}
+        }
+    }
+
+    fn position(&mut self, game: &Game, strategies: &[&mut dyn PlayerStrategy]) {
+        game.dump(strategies);
+    }
+}
+
+/// Reasons why a move is not allowed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// the game is already over
+    GameOver(),
+    /// the hand has no card at this position
+    PositionOutOfRange(u8),
+    /// discarding is not allowed while all 8 clue tokens are available
+    DiscardWithMaxClues(),
+    /// the clue receiver is the active player or does not exist
+    InvalidClueReceiver(u8),
+    /// no clue tokens are left
+    NoClueTokens(),
+    /// the clue color does not exist in the variant or the rank is not between 1 and 5
+    InvalidClue(Clue),
+    /// the clue touches no card (only allowed with the empty clues option)
+    EmptyClue(),
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GameOver() => f.write_str("the game is already over"),
+            Self::PositionOutOfRange(pos) => write!(f, "there is no card at position {pos}"),
+            Self::DiscardWithMaxClues() => f.write_str("can't discard with 8 clue tokens"),
+            Self::InvalidClueReceiver(player) => write!(f, "can't clue player {player}"),
+            Self::NoClueTokens() => f.write_str("no clue tokens are left"),
+            Self::InvalidClue(clue) => write!(f, "{clue:?} is not possible in this variant"),
+            Self::EmptyClue() => f.write_str("the clue touches no card"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Table options of a hanab.live game
+///
+/// The simulator implements the hand size options and empty clues. Deck plays,
+/// all or nothing and detrimental characters are only parsed (to read replays
+/// and lobby messages): games can't be set up with them, see [`GameOptions::unsupported`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameOptions {
+    pub one_extra_card: bool,
+    pub one_less_card: bool,
+    pub deck_plays: bool,
+    pub empty_clues: bool,
+    pub all_or_nothing: bool,
+    pub detrimental_characters: bool,
+}
+
+impl GameOptions {
+    /// Number of cards each player holds (as on hanab.live: 4 cards with 4 and 5 players)
+    pub fn hand_size(&self, num_players: u8) -> u8 {
+        let size = match num_players {
+            2 | 3 => 5,
+            4 | 5 => 4,
+            6 => 3,
+            _ => unimplemented!(),
+        };
+        size + self.one_extra_card as u8 - self.one_less_card as u8
+    }
+
+    fn flags(&mut self) -> [(char, &mut bool); 6] {
+        [
+            ('x', &mut self.one_extra_card),
+            ('l', &mut self.one_less_card),
+            ('d', &mut self.deck_plays),
+            ('e', &mut self.empty_clues),
+            ('a', &mut self.all_or_nothing),
+            ('c', &mut self.detrimental_characters),
+        ]
+    }
+
+    /// Name of the first enabled option the simulator does not implement
+    pub fn unsupported(&self) -> Option<&'static str> {
+        [
+            (self.deck_plays, "deck plays"),
+            (self.all_or_nothing, "all or nothing"),
+            (self.detrimental_characters, "detrimental characters"),
+        ]
+        .into_iter()
+        .find(|(enabled, _name)| *enabled)
+        .map(|(_enabled, name)| name)
+    }
+
+    /// Enabled options as flag characters (appended to the variant id in replay URLs)
+    pub fn url_flags(&self) -> String {
+        let mut options = *self;
+        options
+            .flags()
+            .into_iter()
+            .filter(|(_flag, enabled)| **enabled)
+            .map(|(flag, _enabled)| flag)
+            .collect()
+    }
+
+    /// Parses flag characters as produced by `url_flags`
+    ///
+    /// ```
+    /// use raikan::game::GameOptions;
+    /// let options = GameOptions::from_url_flags("xe").expect("valid flags");
+    /// assert!(options.one_extra_card && options.empty_clues);
+    /// assert_eq!(options.url_flags(), "xe");
+    /// assert_eq!(GameOptions::from_url_flags("?"), None);
+    /// ```
+    pub fn from_url_flags(flags: &str) -> Option<Self> {
+        let mut options = Self::default();
+        for flag in flags.chars() {
+            *options
+                .flags()
+                .into_iter()
+                .find(|(option_flag, _enabled)| *option_flag == flag)?
+                .1 = true;
+        }
+        Some(options)
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct HanabiLiveOptions {
     variant: String,
+    #[serde(flatten)]
+    options: GameOptions,
+}
+
+impl Default for HanabiLiveOptions {
+    fn default() -> Self {
+        Self {
+            variant: Variant::no_variant().name().to_string(),
+            options: GameOptions::default(),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-struct HanabiLiveGame {
-    players: Vec<String>,
-    deck: Vec<HanabiLiveCard>,
-    actions: Vec<HanabiLiveAction>,
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct HanabiLiveGame {
+    pub(crate) players: Vec<String>,
+    pub(crate) deck: Vec<HanabiLiveCard>,
+    pub(crate) actions: Vec<HanabiLiveAction>,
+    #[serde(default)]
     options: HanabiLiveOptions,
 }
 
+fn default_player_names(num_players: usize) -> Vec<String> {
+    let mut names = vec![
+        "Alice".to_string(),
+        "Bob".to_string(),
+        "Cathy".to_string(),
+        "Donold".to_string(),
+        "Emily".to_string(),
+        "F".to_string(),
+    ];
+    names.truncate(num_players);
+    names
+}
+
+const BASE62_CHARS: &str = "abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+impl HanabiLiveGame {
+    /// Decodes a `https://hanab.live/replay-json/...` link (the prefix is optional)
+    pub(crate) fn from_url(url: &str) -> Result<Self, ReplayError> {
+        let encoded = url
+            .split_once("replay-json/")
+            .map_or(url, |(_prefix, encoded)| encoded);
+        let mut parts = encoded.split(',');
+        let (Some(deck), Some(actions), Some(options), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ReplayError::InvalidUrl(
+                "expected deck, actions and options separated by commas".to_string(),
+            ));
+        };
+        Self::from_url_parts(deck, actions, options)
+    }
+
+    /// Decodes the three comma-separated parts of a hanab.live `replay-json` URL
+    fn from_url_parts(deck: &str, actions: &str, options: &str) -> Result<Self, ReplayError> {
+        let invalid = |message: &str| ReplayError::InvalidUrl(message.to_string());
+        let digit = |c: Option<char>, message: &str| {
+            c.and_then(|c| c.to_digit(10))
+                .map(|d| d as usize)
+                .ok_or_else(|| invalid(message))
+        };
+        let base62 = |c: char| {
+            BASE62_CHARS
+                .find(c)
+                .ok_or_else(|| ReplayError::InvalidUrl(format!("{c:?} is not a base62 character")))
+        };
+
+        // options: variant id followed by the option flags
+        let (variant_id, flags) = options.split_at(
+            options
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(options.len()),
+        );
+        let variant_id = variant_id
+            .parse()
+            .map_err(|_| invalid("the options must start with the variant id"))?;
+        let variant = Variant::from_hanabi_live_id(variant_id)
+            .ok_or(ReplayError::UnknownVariantId(variant_id))?;
+        let options = GameOptions::from_url_flags(flags)
+            .ok_or_else(|| ReplayError::UnknownOptions(flags.to_string()))?;
+
+        // deck: number of players, min and max rank, then one character per card
+        let mut deck_chars = deck.chars();
+        let num_players = digit(
+            deck_chars.next(),
+            "the deck must start with the player count",
+        )?;
+        if !(2..=6).contains(&num_players) {
+            return Err(invalid("only games with 2 to 6 players are supported"));
+        }
+        let min_rank = digit(deck_chars.next(), "the deck must specify the minimal rank")?;
+        let max_rank = digit(deck_chars.next(), "the deck must specify the maximal rank")?;
+        if min_rank > max_rank {
+            return Err(invalid(
+                "the minimal rank must not be larger than the maximal one",
+            ));
+        }
+        let mut cards = Vec::new();
+        for card_char in deck_chars {
+            let index = base62(card_char)?;
+            let num_ranks = max_rank - min_rank + 1;
+            cards.push(HanabiLiveCard {
+                suit_index: (index / num_ranks) as u8,
+                rank: (index % num_ranks + min_rank) as u8,
+            });
+        }
+
+        // actions: min and max action type, then two characters per action
+        let mut action_chars = actions.chars();
+        let min_action = digit(
+            action_chars.next(),
+            "the actions must specify the minimal type",
+        )?;
+        let max_action = digit(
+            action_chars.next(),
+            "the actions must specify the maximal type",
+        )?;
+        if min_action > max_action {
+            return Err(invalid(
+                "the minimal action type must not be larger than the maximal one",
+            ));
+        }
+        let mut decoded_actions = Vec::new();
+        while let Some(action_char) = action_chars.next() {
+            let target_char = action_chars
+                .next()
+                .ok_or_else(|| invalid("the target of the last action is missing"))?;
+            let num_types = max_action - min_action + 1;
+            let action_num = base62(action_char)?;
+            let value = action_num / num_types;
+            decoded_actions.push(HanabiLiveAction {
+                action: (action_num % num_types + min_action) as u8,
+                target: base62(target_char)? as u8,
+                value: (value > 0).then(|| (value - 1) as u8),
+            });
+        }
+
+        Ok(Self {
+            players: default_player_names(num_players),
+            deck: cards,
+            actions: decoded_actions,
+            options: HanabiLiveOptions {
+                variant: variant.name().to_string(),
+                options,
+            },
+        })
+    }
+}
+
 type Hand = VecDeque<CardState>;
 
+/// Why a strategy played a card that turned out to be unplayable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MisplayCause {
+    /// the card was thought to be finessed
+    WrongFinesse(),
+    /// the card was thought to be prompted by a clue to another card
+    WrongPrompt(),
+    /// the card waited for other cards to be played first
+    DelayedPlay(),
+    /// the card was marked as playable although not all its options were playable anymore
+    StalePlayFlag(),
+    /// all options of the card were playable, but the actual card was not among them
+    WrongInference(),
+    Unexplained(),
+}
+
+impl MisplayCause {
+    pub const ALL: [Self; 6] = [
+        Self::WrongFinesse(),
+        Self::WrongPrompt(),
+        Self::DelayedPlay(),
+        Self::StalePlayFlag(),
+        Self::WrongInference(),
+        Self::Unexplained(),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WrongFinesse() => "wrong finesse",
+            Self::WrongPrompt() => "wrong prompt",
+            Self::DelayedPlay() => "delayed play",
+            Self::StalePlayFlag() => "stale play flag",
+            Self::WrongInference() => "wrong inference",
+            Self::Unexplained() => "unexplained",
+        }
+    }
+}
+
+/// A strategy's explanation for one of its misplays
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MisplayReport {
+    pub cause: MisplayCause,
+    /// what the strategy knew about the card, for debugging
+    pub belief: String,
+}
+
+/// A misplayed card as recorded by the game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Misplay {
+    pub turn: u8,
+    pub player: u8,
+    pub pos: u8,
+    pub card: Card,
+    /// `None` if the strategy can't explain its moves
+    pub report: Option<MisplayReport>,
+}
+
+#[derive(Clone)]
 pub struct Game {
-    pub suits: Vec<Suit>,
+    pub variant: Variant,
+    pub options: GameOptions,
+    /// why the game became invalid
+    pub invalid_move: Option<MoveError>,
     pub score_integral: u16,
     pub discarded: BTreeMap<Card, u8>,
+    /// number of played cards per suit
+    ///
+    /// This used to be the top rank of the stack, which is the same for suits played
+    /// upwards; use [`PlayOrder::rank`] of `play_orders` to get the top rank.
     pub played: Vec<u8>,
+    pub play_orders: Vec<PlayOrder>,
     deck: VecDeque<Card>,
     hands: Vec<Hand>,
     active_player: usize,
     pub state: GameState,
-    debug: bool,
     replay: HanabiLiveGame,
     seed: u64,
+    /// started from a position hanab.live can not represent
+    custom_position: bool,
+    /// not part of clones, so snapshots and branches don't report to them
+    observers: Observers,
     pub status: GameStatus,
+    /// misplayed cards with the explanations of their players
+    pub misplays: Vec<Misplay>,
+}
+
+/// Everything the active player can see when making a move
+pub struct PlayerView<'a> {
+    pub status: &'a GameStatus,
+    /// number of played cards per suit (in the order of the variant suits)
+    pub played: &'a [u8],
+    pub play_orders: &'a [PlayOrder],
+    pub discarded: &'a BTreeMap<Card, u8>,
+    /// number of cards left to draw
+    pub deck_size: usize,
+    /// absolute index of the active player
+    pub active_player: u8,
+    /// hands relative to the active player; cards of the own hand (index 0) are unknown
+    pub hands: Vec<Vec<VisibleCard<'a>>>,
 }
 
+impl PlayerView<'_> {
+    pub fn num_players(&self) -> u8 {
+        self.hands.len() as u8
+    }
+}
+
+#[derive(Clone)]
 pub struct GameStatus {
     pub turn: u8,
     pub score: u8,
@@ -247,6 +1243,10 @@ pub enum ClueColor {
     Yellow(),
     Blue(),
     Purple(),
+    Teal(),
+    Pink(),
+    Brown(),
+    Black(),
 }
 
 impl ClueColor {
@@ -257,6 +1257,10 @@ impl ClueColor {
             ClueColor::Blue() => Suit::Blue(),
             ClueColor::Green() => Suit::Green(),
             ClueColor::Purple() => Suit::Purple(),
+            ClueColor::Pink() => Suit::Pink(),
+            ClueColor::Teal() => Suit::Teal(),
+            ClueColor::Brown() => Suit::Brown(),
+            ClueColor::Black() => Suit::Black(),
         }
     }
 }
@@ -272,11 +1276,55 @@ pub enum Move {
     Discard(u8),
     Play(u8),
     Clue(u8, Clue),
+    /// the active player stops the game, see [`Game::terminate`]
+    Resign(),
+}
+
+impl Move {
+    /// All legal moves of the active player
+    ///
+    /// `hand_sizes` lists the number of cards of every player, relative to the
+    /// active player, and `touches` tells whether a clue to a (relative) player
+    /// touches any card. The own cards are never inspected, so strategies can
+    /// use this from their point of view as well. Resigning is always allowed
+    /// and not listed.
+    pub fn legal_moves(
+        variant: &Variant,
+        options: &GameOptions,
+        clues: u8,
+        hand_sizes: &[u8],
+        touches: impl Fn(u8, Clue) -> bool,
+    ) -> Vec<Move> {
+        let mut moves = Vec::new();
+        if clues < 8 {
+            moves.extend((0..hand_sizes[0]).map(Move::Discard));
+        }
+        moves.extend((0..hand_sizes[0]).map(Move::Play));
+        if clues > 0 {
+            for player in 1..hand_sizes.len() as u8 {
+                let clues = variant
+                    .clue_colors()
+                    .map(Clue::Color)
+                    .chain((1..=5).map(Clue::Rank));
+                for clue in clues {
+                    if options.empty_clues || touches(player, clue) {
+                        moves.push(Move::Clue(player, clue));
+                    }
+                }
+            }
+        }
+        moves
+    }
 }
 
 pub trait PlayerStrategy: std::fmt::Debug {
-    fn init(&mut self, num_players: u8, start_player: u8);
-    fn act(&mut self, status: &GameStatus) -> Move;
+    /// Name of the strategy, e.g. for the players of replays
+    fn name(&self) -> String {
+        "unknown".to_string()
+    }
+
+    fn init(&mut self, num_players: u8, start_player: u8, variant: Variant, options: GameOptions);
+    fn act(&mut self, view: &PlayerView) -> Move;
 
     fn drawn(&mut self, player: usize, card: Card);
     fn own_drawn(&mut self);
@@ -285,125 +1333,140 @@ pub trait PlayerStrategy: std::fmt::Debug {
 
     fn discarded(&mut self, player: usize, pos: usize, card: Card);
     fn clued(&mut self, who: usize, whom: usize, clue: Clue, touched: PositionSet);
+
+    /// Explains why the own card at `pos` was played, called after it misplayed
+    /// (but before [`PlayerStrategy::played`] reports the misplay)
+    fn explain_misplay(&self, _pos: usize, _card: Card) -> Option<MisplayReport> {
+        None
+    }
+}
+
+impl<P: PlayerStrategy + ?Sized> PlayerStrategy for Box<P> {
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn init(&mut self, num_players: u8, start_player: u8, variant: Variant, options: GameOptions) {
+        (**self).init(num_players, start_player, variant, options)
+    }
+
+    fn act(&mut self, view: &PlayerView) -> Move {
+        (**self).act(view)
+    }
+
+    fn drawn(&mut self, player: usize, card: Card) {
+        (**self).drawn(player, card)
+    }
+
+    fn own_drawn(&mut self) {
+        (**self).own_drawn()
+    }
+
+    fn played(&mut self, player: usize, pos: usize, card: Card, successful: bool) {
+        (**self).played(player, pos, card, successful)
+    }
+
+    fn discarded(&mut self, player: usize, pos: usize, card: Card) {
+        (**self).discarded(player, pos, card)
+    }
+
+    fn clued(&mut self, who: usize, whom: usize, clue: Clue, touched: PositionSet) {
+        (**self).clued(who, whom, clue, touched)
+    }
+
+    fn explain_misplay(&self, pos: usize, card: Card) -> Option<MisplayReport> {
+        (**self).explain_misplay(pos, card)
+    }
+}
+
+/// Borrows the players as the strategies [`Game`] takes, one seat per player
+pub fn seats<P: PlayerStrategy>(players: &mut [P]) -> Vec<&mut dyn PlayerStrategy> {
+    players
+        .iter_mut()
+        .map(|player| player as &mut dyn PlayerStrategy)
+        .collect()
 }
 
 impl Game {
     pub fn new(players: &mut [&mut dyn PlayerStrategy], debug: bool, seed: u64) -> Self {
-        let suits = vec![
-            Suit::Red(),
-            Suit::Yellow(),
-            Suit::Green(),
-            Suit::Blue(),
-            Suit::Purple(),
-        ];
+        Self::with_variant(players, Variant::no_variant(), debug, seed)
+    }
 
-        let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
-        let mut deck = Vec::with_capacity(10 * suits.len());
-        for suit in suits.iter() {
-            for rank in 1..=5 {
-                for _count in 0..suit.card_count(rank) {
-                    deck.push(Card { suit: *suit, rank });
-                }
-            }
-        }
-        deck.shuffle(&mut rng);
+    /// Plays a whole seeded game of the default variant
+    pub fn play_seed<P: PlayerStrategy>(players: &mut [P], seed: u64) -> Self {
+        let mut players = seats(players);
+        let mut game = Self::new(&mut players, false, seed);
+        game.run(&mut players);
+        game
+    }
 
-        let mut hands = Vec::new();
-        let num_cards = match players.len() {
-            2 => 5,
-            3 => 5,
-            4 => 4,
-            5 => 5,
-            6 => 3,
-            _ => unimplemented!(),
-        };
+    pub fn with_variant(
+        players: &mut [&mut dyn PlayerStrategy],
+        variant: Variant,
+        debug: bool,
+        seed: u64,
+    ) -> Self {
+        Self::with_options(players, variant, GameOptions::default(), debug, seed)
+            .expect("games need 2 to 6 players")
+    }
 
-        for _ in players.iter() {
-            hands.push(Hand::with_capacity(num_cards));
+    /// Sets up a seeded game with table options (fails for options the simulator
+    /// does not implement)
+    pub fn with_options(
+        players: &mut [&mut dyn PlayerStrategy],
+        variant: Variant,
+        options: GameOptions,
+        debug: bool,
+        seed: u64,
+    ) -> Result<Self, SetupError> {
+        if !(2..=6).contains(&players.len()) {
+            return Err(SetupError::InvalidPlayerCount(players.len() as u8));
         }
-
-        let mut player_names = vec![
-            "Alice".to_string(),
-            "Bob".to_string(),
-            "Cathy".to_string(),
-            "Donold".to_string(),
-            "Emily".to_string(),
-            "F".to_string(),
-        ];
-        player_names.truncate(players.len());
-
-        let mut game = Self {
-            score_integral: 0,
-            deck: deck.into(),
-            discarded: BTreeMap::new(),
-            played: vec![0; suits.len()],
-            hands,
-            status: GameStatus {
-                num_strikes: 0,
-                clues: 8,
-                score: 0,
-                max_score: 5 * suits.len() as u8,
-                turn: 0,
-                blind_plays: 0,
-            },
-            suits,
-            active_player: 0,
-            state: GameState::Early(),
-            debug,
-            replay: HanabiLiveGame {
-                actions: Vec::new(),
-                deck: Vec::new(),
-                options: HanabiLiveOptions {
-                    variant: "No Variant".to_string(),
-                },
-                players: player_names,
-            },
-            seed,
-        };
+        if let Some(option) = options.unsupported() {
+            return Err(SetupError::UnsupportedOption(option));
+        }
+        let mut game = Self::empty_with_options(players.len() as u8, variant, options);
+        game.set_strategy_names(players);
+        game.deck = Self::shuffled_deck(variant, seed).into();
+        if debug {
+            game.add_observer(GameLogger::shared());
+        }
+        game.seed = seed;
         for (pos, strategy) in players.iter_mut().enumerate() {
-            strategy.init(game.num_players(), pos as u8);
+            strategy.init(game.num_players(), pos as u8, variant, options);
         }
+        game.deal(players);
+        Ok(game)
+    }
 
-        for player in 0..players.len() {
-            for _ in 0..num_cards {
-                game.draw_card(player, players);
+    /// Deck of a seeded game in drawing order (the first cards are dealt)
+    pub fn shuffled_deck(variant: Variant, seed: u64) -> Vec<Card> {
+        let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
+        let mut deck = Vec::with_capacity(variant.deck_size());
+        for suit in variant.suits().iter() {
+            for rank in 1..=5 {
+                let card = Card { suit: *suit, rank };
+                for _count in 0..variant.card_count(&card) {
+                    deck.push(card);
+                }
             }
         }
-
-        game
+        deck.shuffle(&mut rng);
+        deck
     }
 
     pub fn empty(num_players: u8) -> Self {
-        let suits = vec![
-            Suit::Red(),
-            Suit::Yellow(),
-            Suit::Green(),
-            Suit::Blue(),
-            Suit::Purple(),
-        ];
-
-        let mut player_names = vec![
-            "Alice".to_string(),
-            "Bob".to_string(),
-            "Cathy".to_string(),
-            "Donold".to_string(),
-            "Emily".to_string(),
-            "F".to_string(),
-        ];
-        player_names.truncate(num_players as usize);
+        Self::empty_with_options(num_players, Variant::no_variant(), GameOptions::default())
+    }
+
+    pub fn empty_with_options(num_players: u8, variant: Variant, options: GameOptions) -> Self {
+        let player_names = default_player_names(num_players as usize);
 
         let mut hands = Vec::new();
-        let num_cards = match num_players {
-            2 => 5,
-            3 => 5,
-            4 => 4,
-            5 => 4,
-            6 => 3,
-            _ => unimplemented!(),
-        };
+        let num_cards = options.hand_size(num_players);
 
         for _ in 0..num_players {
-            hands.push(Hand::with_capacity(num_cards));
+            hands.push(Hand::with_capacity(num_cards as usize));
         }
 
         Self {
@@ -411,140 +1474,159 @@ impl Game {
                 num_strikes: 0,
                 clues: 8,
                 score: 0,
-                max_score: 5 * suits.len() as u8,
+                max_score: variant.max_score(),
                 turn: 0,
                 blind_plays: 0,
             },
             score_integral: 0,
             deck: VecDeque::new(),
             discarded: BTreeMap::new(),
-            played: vec![0; suits.len()],
+            played: vec![0; variant.len()],
+            play_orders: variant
+                .suits()
+                .iter()
+                .map(|suit| variant.play_order(suit))
+                .collect(),
             hands,
-            suits,
+            variant,
+            options,
+            invalid_move: None,
             active_player: 0,
             state: GameState::Early(),
-            debug: false,
             replay: HanabiLiveGame {
                 actions: Vec::new(),
                 deck: Vec::new(),
                 options: HanabiLiveOptions {
-                    variant: "No Variant".to_string(),
+                    variant: variant.name().to_string(),
+                    options,
                 },
                 players: player_names,
             },
             seed: 0,
+            custom_position: false,
+            observers: Observers::default(),
+            misplays: Vec::new(),
         }
     }
 
+    /// Replays the first `turn` actions of a hanab.live replay URL
+    /// (given as its comma-separated deck, actions and options parts)
     pub fn from_replay(
         turn: u8,
         deck: &str,
         actions: &str,
         options: &str,
         players: &mut [&mut dyn PlayerStrategy],
-    ) -> Self {
-        assert_eq!(options, "0");
-        let base62_chars = "abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let mut deck_chars = deck.chars();
-        let num_players = deck_chars
-            .next()
-            .expect("deck should be non-empty")
-            .to_digit(10)
-            .expect("player count must be a number") as u8;
-        assert_eq!(deck_chars.next(), Some('1'));
-        assert_eq!(deck_chars.next(), Some('5'));
-        let mut game = Self::empty(num_players);
-        game.debug = true;
-        for card_char in deck_chars {
-            let index = base62_chars
-                .find(card_char)
-                .expect("desk card must be a valid base62 character");
-            let rank = index % 5 + 1;
-            let suit_index = index / 5;
-            game.deck.push_back(Card {
-                rank: rank as u8,
-                suit: game.suits[suit_index],
-            });
-        }
+    ) -> Result<Self, ReplayError> {
+        let replay = HanabiLiveGame::from_url_parts(deck, actions, options)?;
+        Self::from_hanabi_live_game(replay, players, turn as usize, true)
+    }
 
-        for (pos, strategy) in players.iter_mut().enumerate() {
-            strategy.init(num_players, pos as u8);
-        }
+    /// Replays the first `turn` actions of a `https://hanab.live/replay-json/...` link
+    pub fn from_replay_url_at(
+        turn: u8,
+        url: &str,
+        players: &mut [&mut dyn PlayerStrategy],
+    ) -> Result<Self, ReplayError> {
+        let replay = HanabiLiveGame::from_url(url)?;
+        Self::from_hanabi_live_game(replay, players, turn as usize, false)
+    }
 
-        // 1. draw initial cards:
-        let num_cards = match num_players {
-            2 => 5,
-            3 => 5,
-            4 => 4,
-            5 => 5,
-            6 => 3,
-            _ => unimplemented!(),
-        };
-        for player in 0..players.len() {
-            for _ in 0..num_cards {
-                game.draw_card(player, players);
-            }
-        }
+    /// Number of players of a `https://hanab.live/replay-json/...` link
+    pub fn replay_url_num_players(url: &str) -> Result<usize, ReplayError> {
+        Ok(HanabiLiveGame::from_url(url)?.players.len())
+    }
 
-        // 2 process actions:
-        let mut action_chars = actions.chars();
-        let min_action = action_chars
-            .next()
-            .expect("actions should be non-empty")
-            .to_digit(10)
-            .expect("action min must be a number") as usize;
-        let max_action = action_chars
-            .next()
-            .expect("deck should be non-empty")
-            .to_digit(10)
-            .expect("action max must be a number") as usize;
-        let mut current_turn = 0;
-        let mut active_player = 0;
-        while current_turn < turn {
-            let action_num = base62_chars
-                .find(action_chars.next().expect("target value is missing"))
-                .expect("Actions must only be valid 62 characters");
-            let action = action_num % (max_action - min_action + 1) + min_action;
-            let mut value = action_num / (max_action - min_action + 1);
-            value = value.saturating_sub(1);
-            let target = base62_chars
-                .find(action_chars.next().expect("target value is missing"))
-                .expect("Actions must only be valid 62 characters");
-            let mut target_pos = None;
-            for (pos, slot) in game.hands[active_player as usize].iter().enumerate() {
-                if slot.index as usize == target {
-                    target_pos = Some(pos as u8)
+    /// Replays a complete `https://hanab.live/replay-json/...` link
+    pub fn from_replay_url(
+        url: &str,
+        players: &mut [&mut dyn PlayerStrategy],
+    ) -> Result<Self, ReplayError> {
+        let replay = HanabiLiveGame::from_url(url)?;
+        Self::from_hanabi_live_game(replay, players, usize::MAX, false)
+    }
+
+    /// Replays a game exported from hanab.live (as JSON) through the strategies
+    pub fn from_hanabi_live_json(
+        json: &str,
+        players: &mut [&mut dyn PlayerStrategy],
+    ) -> Result<Self, ReplayError> {
+        let replay: HanabiLiveGame = serde_json::from_str(json)
+            .map_err(|error| ReplayError::InvalidJson(error.to_string()))?;
+        Self::from_hanabi_live_game(replay, players, usize::MAX, false)
+    }
+
+    fn from_hanabi_live_game(
+        replay: HanabiLiveGame,
+        players: &mut [&mut dyn PlayerStrategy],
+        max_actions: usize,
+        debug: bool,
+    ) -> Result<Self, ReplayError> {
+        if replay.players.len() != players.len() {
+            return Err(ReplayError::PlayerCount(replay.players.len()));
+        }
+        let variant = Variant::from_name(&replay.options.variant)
+            .ok_or_else(|| ReplayError::UnknownVariant(replay.options.variant.clone()))?;
+        let mut deck = Vec::with_capacity(replay.deck.len());
+        for (index, card) in replay.deck.iter().enumerate() {
+            let suit = variant
+                .suits()
+                .get(card.suit_index as usize)
+                .ok_or(ReplayError::InvalidCard(index))?;
+            deck.push(Card {
+                suit: *suit,
+                rank: card.rank,
+            });
+        }
+        let mut game = GameBuilder::new(deck)
+            .variant(variant)
+            .options(replay.options.options)
+            .debug(debug)
+            .build(players)
+            .map_err(ReplayError::InvalidSetup)?;
+        game.replay.players = replay.players;
+
+        for (index, action) in replay.actions.iter().take(max_actions).enumerate() {
+            if action.action == 4 {
+                // game over: normal ends and strikeouts are detected by the game itself
+                if action.target >= game.num_players() {
+                    return Err(ReplayError::InvalidAction(index));
+                }
+                if !game.is_over() {
+                    game.terminate(action.target, action.value.unwrap_or(END_TERMINATED));
                 }
+                break;
             }
-            match action {
-                0 => game.execute(
-                    game::Move::Play(target_pos.expect("card not in players hand")),
-                    players,
-                ),
-                1 => game.execute(
-                    game::Move::Discard(target_pos.expect("card not in players hand")),
-                    players,
-                ),
-                2 => game.execute(
-                    game::Move::Clue(
-                        (num_players + target as u8 - active_player) % num_players,
-                        game::Clue::Color(game.suits[value].clue_color()),
-                    ),
-                    players,
-                ),
-                3 => game.execute(
-                    game::Move::Clue(
-                        (num_players + target as u8 - active_player) % num_players,
-                        game::Clue::Rank(value as u8),
-                    ),
-                    players,
-                ),
-                _ => panic!("Unknown action code (only 1..3 implemented)"),
+            let action = game
+                .hanabi_live_move(action)
+                .ok_or(ReplayError::InvalidAction(index))?;
+            game.try_execute(action, players)
+                .map_err(|error| ReplayError::InvalidMove(index, error))?;
+        }
+        Ok(game)
+    }
+
+    /// Converts a hanab.live action of the active player into a move
+    fn hanabi_live_move(&self, action: &HanabiLiveAction) -> Option<Move> {
+        let num_players = self.num_players();
+        let target_pos = self.hands[self.active_player]
+            .iter()
+            .position(|card_state| card_state.index == action.target);
+        // clue targets are absolute player indices, moves use ones relative to the active player
+        let receiver = || {
+            (action.target < num_players)
+                .then(|| (num_players + action.target - self.active_player as u8) % num_players)
+        };
+        match action.action {
+            0 => Some(Move::Play(target_pos? as u8)),
+            1 => Some(Move::Discard(target_pos? as u8)),
+            2 => {
+                let color = self.variant.clue_colors().nth(action.value? as usize)?;
+                Some(Move::Clue(receiver()?, Clue::Color(color)))
             }
-            active_player = (active_player + 1) % num_players;
-            current_turn += 1;
+            3 => Some(Move::Clue(receiver()?, Clue::Rank(action.value?))),
+            _ => None,
         }
-        game
     }
 
     pub fn num_players(&self) -> u8 {
@@ -555,11 +1637,11 @@ impl Game {
         self.hands[(self.active_player + player) % self.hands.len()].len() as u8
     }
 
-    pub fn dump(&self, strategies: &mut [&mut dyn PlayerStrategy]) {
+    pub fn dump(&self, strategies: &[&mut dyn PlayerStrategy]) {
         println!("Game:");
         println!(
             "  suits={:?} turn={} score={}/{} (sum: {}) strikes={} clues={} state={:?}",
-            self.suits,
+            self.variant.suits(),
             self.status.turn,
             self.status.score,
             self.status.max_score,
@@ -569,7 +1651,7 @@ impl Game {
             self.state,
         );
         print!("  played:");
-        for (pos, suit) in self.suits.iter().enumerate() {
+        for (pos, suit) in self.variant.suits().iter().enumerate() {
             print!(" {}={}", suit, self.played[pos]);
         }
         println!();
@@ -583,51 +1665,121 @@ impl Game {
     }
 
     pub fn run(&mut self, strategies: &mut [&mut dyn PlayerStrategy]) -> u8 {
-        if self.debug {
-            self.dump(strategies);
+        self.emit_position(strategies);
+        while self.step(strategies).is_some() {}
+        self.status.score
+    }
+
+    /// Captures the full engine state to restore or branch from it later
+    ///
+    /// Strategies keep their own state, so they need to be cloned alongside
+    /// the snapshot (e.g. [`crate::hyphenated::HyphenatedPlayer`] is `Clone`).
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot(self.clone())
+    }
+
+    /// Rewinds (or forwards) the game to the state of the snapshot
+    pub fn restore(&mut self, snapshot: &GameSnapshot) {
+        let observers = std::mem::take(&mut self.observers);
+        self.clone_from(&snapshot.0);
+        self.observers = observers;
+    }
+
+    /// Lets the active player make one move
+    ///
+    /// Returns the move, or `None` if the game is already over. Moves from
+    /// outside the strategies can be injected with [`Game::try_execute`].
+    pub fn step(&mut self, strategies: &mut [&mut dyn PlayerStrategy]) -> Option<Move> {
+        if self.is_over() {
+            return None;
         }
-        loop {
-            match self.state {
-                GameState::Early() => {
-                    self.play(strategies);
-                }
-                GameState::Mid() => {
-                    self.play(strategies);
-                }
-                GameState::Final(0) => {
-                    self.replay.actions.push(HanabiLiveAction {
-                        action: 4,
-                        target: self.active_player as u8,
-                        value: Some(1), // normal end
-                    });
-                    if self.status.score == 25 {
-                        self.state = GameState::Won()
-                    } else {
-                        self.state = GameState::Finished()
-                    }
-                }
-                GameState::Final(remaining) => {
-                    self.play(strategies);
-                    if self.state == GameState::Final(remaining) {
-                        self.state = GameState::Final(remaining - 1)
-                    }
-                }
-                _ => break,
-            }
-            if self.debug {
-                self.dump(strategies);
+        let action = strategies[self.active_player].act(&self.player_view());
+        self.execute(action, strategies);
+        self.emit_position(strategies);
+        Some(action)
+    }
+
+    /// What the active player can see of the game
+    pub fn player_view(&self) -> PlayerView<'_> {
+        let num_players = self.hands.len();
+        PlayerView {
+            status: &self.status,
+            played: &self.played,
+            play_orders: &self.play_orders,
+            discarded: &self.discarded,
+            deck_size: self.deck.len(),
+            active_player: self.active_player as u8,
+            hands: (0..num_players)
+                .map(|player| {
+                    self.hands[(self.active_player + player) % num_players]
+                        .iter()
+                        .map(|card_state| card_state.visible(player == 0))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state.is_over()
+    }
+
+    /// Index of the player making the next move
+    pub fn active_player(&self) -> usize {
+        self.active_player
+    }
+
+    /// Whether the card can be played successfully right now
+    pub fn is_playable(&self, card: &Card) -> bool {
+        let suit_index = self.variant.suit_index(&card.suit);
+        match self.play_orders[suit_index] {
+            PlayOrder::Open() => card.rank == 1 || card.rank == 5,
+            order => order.rank(self.played[suit_index]) == Some(card.rank),
+        }
+    }
+
+    fn deal(&mut self, strategies: &mut [&mut dyn PlayerStrategy]) {
+        let num_cards = self.options.hand_size(self.num_players());
+        for player in 0..self.hands.len() {
+            for _ in 0..num_cards {
+                self.draw_card(player, strategies);
             }
         }
-        self.status.score
     }
 
-    fn played_rank(&self, suit: &Suit) -> u8 {
-        for (pos, current_suit) in self.suits.iter().enumerate() {
-            if current_suit == suit {
-                return self.played[pos];
+    /// Lets strategies know about a card that was played or discarded
+    /// before the set up position (as if player 0 drew and removed it)
+    fn remove_for_setup(
+        &mut self,
+        card: Card,
+        played: bool,
+        strategies: &mut [&mut dyn PlayerStrategy],
+    ) {
+        for (notify_player, strategy) in strategies.iter_mut().enumerate() {
+            let player = self.relative_player_index(0, notify_player);
+            if notify_player == 0 {
+                strategy.own_drawn();
+            } else {
+                strategy.drawn(player, card);
+            }
+            if played {
+                strategy.played(player, 0, card, true);
+            } else {
+                strategy.discarded(player, 0, card);
             }
         }
-        0
+    }
+
+    fn add_to_stack(&mut self, card: Card) {
+        let suit_index = self.variant.suit_index(&card.suit);
+        let order = self.play_orders[suit_index];
+        self.play_orders[suit_index] = order.started_with(card.rank);
+        self.played[suit_index] += 1;
+        self.status.score += 1;
+        if order == PlayOrder::Open() {
+            // the play order is decided -> cards of the other direction might be lost
+            self.update_max_score();
+        }
     }
 
     fn discard(&mut self, card: Card) {
@@ -636,12 +1788,47 @@ impl Game {
             .entry(card)
             .and_modify(|e| *e += 1)
             .or_insert(1);
-        if count == card.suit.card_count(card.rank) {
+        if count == self.variant.card_count(&card) {
             // a card is lost -> updated maximal possible score based on remaining cards
             self.update_max_score();
         }
     }
 
+    /// Sends all following events of the game to the observer
+    pub fn add_observer(&mut self, observer: SharedObserver) {
+        self.observers.0.push(observer);
+    }
+
+    fn emit_position(&self, strategies: &[&mut dyn PlayerStrategy]) {
+        for observer in self.observers.0.iter() {
+            observer
+                .lock()
+                .expect("observer should not be poisoned")
+                .position(self, strategies);
+        }
+    }
+
+    fn emit(&self, event: GameEvent) {
+        for observer in self.observers.0.iter() {
+            observer
+                .lock()
+                .expect("observer should not be poisoned")
+                .event(&self.status, &event);
+        }
+    }
+
+    fn set_state(&mut self, state: GameState) {
+        let previous = self.state;
+        self.state = state;
+        self.emit(GameEvent::StateChanged {
+            from: previous,
+            to: state,
+        });
+        if state.is_over() {
+            self.emit(GameEvent::GameOver { state });
+        }
+    }
+
     fn relative_player_index(&self, player: usize, receiver: usize) -> usize {
         (self.hands.len() + player - receiver) % self.hands.len()
     }
@@ -649,8 +1836,9 @@ impl Game {
     fn draw_card(&mut self, player: usize, strategies: &mut [&mut dyn PlayerStrategy]) {
         if let Some(card) = self.deck.pop_front() {
             self.hands[player].push_front(CardState::from_card(card, self.replay.deck.len() as u8));
+            self.emit(GameEvent::Drawn { player, card });
             if self.deck.is_empty() {
-                self.state = GameState::Final(self.hands.len() as u8);
+                self.set_state(GameState::Final(self.hands.len() as u8));
             }
             for (notify_player, strategy) in strategies.iter_mut().enumerate() {
                 if notify_player == player {
@@ -661,26 +1849,40 @@ impl Game {
             }
             self.replay.deck.push(HanabiLiveCard {
                 rank: card.rank,
-                suit_index: self.suits.iter().position(|&s| s == card.suit).unwrap() as u8,
+                suit_index: self.variant.suit_index(&card.suit) as u8,
             })
         }
     }
 
     fn update_max_score(&mut self) {
         self.status.max_score = 0;
-        for suit in self.suits.iter() {
-            self.status.max_score += self.max_rank_for_suit(*suit);
+        for suit in self.variant.suits().iter() {
+            self.status.max_score += self.max_stack_height(*suit);
+        }
+    }
+
+    /// Number of cards that can be played on the stack of a suit, given the discarded cards
+    ///
+    /// For suits played upwards, this is the maximal reachable rank.
+    pub fn max_stack_height(&self, suit: Suit) -> u8 {
+        match self.play_orders[self.variant.suit_index(&suit)] {
+            PlayOrder::Open() => self
+                .max_height(suit, PlayOrder::Up())
+                .max(self.max_height(suit, PlayOrder::Down())),
+            order => self.max_height(suit, order),
         }
     }
 
+    #[deprecated(note = "renamed to `max_stack_height`, which counts cards instead of ranks")]
     pub fn max_rank_for_suit(&self, suit: Suit) -> u8 {
+        self.max_stack_height(suit)
+    }
+
+    fn max_height(&self, suit: Suit, order: PlayOrder) -> u8 {
         let mut max = 0;
-        while max < 5 {
-            let card = Card {
-                suit,
-                rank: max + 1,
-            };
-            if *self.discarded.get(&card).unwrap_or(&0) == suit.card_count(max + 1) {
+        while let Some(rank) = order.rank(max) {
+            let card = Card { suit, rank };
+            if *self.discarded.get(&card).unwrap_or(&0) == self.variant.card_count(&card) {
                 return max;
             }
             max += 1;
@@ -688,50 +1890,115 @@ impl Game {
         max
     }
 
-    fn play(&mut self, strategies: &mut [&mut dyn PlayerStrategy]) {
-        let action = strategies[self.active_player].act(&self.status);
-        self.execute(action, strategies);
+    /// Moves the active player is allowed to make right now
+    pub fn legal_moves(&self) -> Vec<Move> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        let num_players = self.hands.len();
+        let hand = |player: u8| &self.hands[(self.active_player + player as usize) % num_players];
+        let hand_sizes: Vec<u8> = (0..num_players as u8)
+            .map(|player| hand(player).len() as u8)
+            .collect();
+        Move::legal_moves(
+            &self.variant,
+            &self.options,
+            self.status.clues,
+            &hand_sizes,
+            |player, clue| {
+                hand(player)
+                    .iter()
+                    .any(|card_state| self.variant.touches(&card_state.card, clue))
+            },
+        )
+    }
+
+    /// Checks whether the active player is allowed to make the given move
+    pub fn validate_move(&self, action: Move) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver());
+        }
+        let hand = &self.hands[self.active_player];
+        match action {
+            Move::Discard(pos) | Move::Play(pos) if pos as usize >= hand.len() => {
+                Err(MoveError::PositionOutOfRange(pos))
+            }
+            Move::Discard(_pos) if self.status.clues == 8 => Err(MoveError::DiscardWithMaxClues()),
+            Move::Discard(_pos) | Move::Play(_pos) => Ok(()),
+            Move::Clue(player, _clue) if player == 0 || player as usize >= self.hands.len() => {
+                Err(MoveError::InvalidClueReceiver(player))
+            }
+            Move::Clue(_player, _clue) if self.status.clues == 0 => Err(MoveError::NoClueTokens()),
+            Move::Clue(player, clue) => self.validate_clue(self.active_player, player, clue),
+            Move::Resign() => Ok(()),
+        }
+    }
+
+    fn validate_clue(&self, giver: usize, player: u8, clue: Clue) -> Result<(), MoveError> {
+        let valid_clue = match clue {
+            Clue::Color(color) => self.variant.clue_colors().any(|other| other == color),
+            Clue::Rank(rank) => (1..=5).contains(&rank),
+        };
+        if !valid_clue {
+            return Err(MoveError::InvalidClue(clue));
+        }
+        let receiver = (giver + player as usize) % self.hands.len();
+        if !self.options.empty_clues
+            && !self.hands[receiver]
+                .iter()
+                .any(|card_state| self.variant.touches(&card_state.card, clue))
+        {
+            return Err(MoveError::EmptyClue());
+        }
+        Ok(())
+    }
+
+    /// Executes the move of the active player if it is valid;
+    /// an invalid move leaves the game unchanged
+    pub fn try_execute(
+        &mut self,
+        action: Move,
+        strategies: &mut [&mut dyn PlayerStrategy],
+    ) -> Result<(), MoveError> {
+        self.validate_move(action)?;
+        self.apply(action, strategies);
+        Ok(())
     }
 
     fn execute(&mut self, action: Move, strategies: &mut [&mut dyn PlayerStrategy]) {
+        if let Err(error) = self.try_execute(action, strategies) {
+            self.status.turn += 1;
+            self.emit(GameEvent::InvalidMove {
+                player: self.active_player,
+                action,
+                error,
+            });
+            self.invalid_move = Some(error);
+            self.invalidate_game();
+        }
+    }
+
+    fn apply(&mut self, action: Move, strategies: &mut [&mut dyn PlayerStrategy]) {
+        if action == Move::Resign() {
+            self.terminate(self.active_player as u8, END_TERMINATED);
+            return;
+        }
+        let previous_state = self.state;
         self.status.turn += 1;
         match action {
             Move::Discard(pos) => {
-                let card = self.hands[self.active_player].remove(pos as usize);
-                if card.is_none() {
-                    println!(
-                        "Invalid move: player {} tried to discard card {} (hand only has {} cards), turn {}, seed {}",
-                        self.active_player,
-                        pos,
-                        self.hands[self.active_player].len(),
-                        self.status.turn,
-                        self.seed,
-                    );
-                    self.invalidate_game();
-                    return;
-                }
-                if self.status.clues < 8 {
-                    self.status.clues += 1;
-                } else {
-                    if self.debug {
-                        println!(
-                            "With 8 clues you can't discard, but player {} did; turn {}, seed {}",
-                            self.active_player, self.status.turn, self.seed,
-                        );
-                    }
-                    self.invalidate_game();
-                    return;
-                }
-                let card = card.unwrap();
-                if self.debug {
-                    println!(
-                        "Player {} discarded {:?} from pos {}",
-                        self.active_player, card, pos
-                    );
-                }
+                let card = self.hands[self.active_player]
+                    .remove(pos as usize)
+                    .expect("validated position");
+                self.status.clues += 1;
                 self.discard(card.card);
+                self.emit(GameEvent::Discarded {
+                    player: self.active_player,
+                    pos,
+                    card: card.card,
+                });
                 if let GameState::Early() = self.state {
-                    self.state = GameState::Mid();
+                    self.set_state(GameState::Mid());
                 }
                 for (notify_player, strategy) in strategies.iter_mut().enumerate() {
                     strategy.discarded(
@@ -748,69 +2015,57 @@ impl Game {
                 self.draw_card(self.active_player, strategies);
             }
             Move::Play(pos) => {
-                let card = self.hands[self.active_player].remove(pos as usize);
-                if card.is_none() {
-                    println!(
-                        "Invalid move: player {} tried to play card {} (hand only has {} cards)",
-                        self.active_player,
-                        pos,
-                        self.hands[self.active_player].len()
-                    );
-                    self.invalidate_game();
-                    return;
-                }
-                let card = card.unwrap();
+                let card = self.hands[self.active_player]
+                    .remove(pos as usize)
+                    .expect("validated position");
                 self.replay.actions.push(HanabiLiveAction {
                     action: 0,
                     target: card.index,
                     value: None,
                 });
-                let success = if self.played_rank(&card.card.suit) + 1 == card.card.rank {
-                    if card.clued {
-                        if self.debug {
-                            println!(
-                                "Player {} played successfully {:?} from pos {}",
-                                self.active_player, card, pos
-                            );
-                        }
-                    } else {
+                let success = if self.is_playable(&card.card) {
+                    if !card.clued {
                         self.status.blind_plays += 1;
-                        if self.debug {
-                            println!(
-                                "Player {} played successfully blind-played {:?} from pos {}",
-                                self.active_player, card, pos
-                            );
-                        }
                     }
-                    for (suit_pos, current_suit) in self.suits.iter().enumerate() {
-                        if *current_suit == card.card.suit {
-                            self.played[suit_pos] += 1;
-                        }
-                    }
-                    self.status.score += 1;
-                    if card.card.rank == 5 && self.status.clues < 8 {
+                    let suit_index = self.variant.suit_index(&card.card.suit);
+                    self.add_to_stack(card.card);
+                    if self.played[suit_index] == 5 && self.status.clues < 8 {
                         self.status.clues += 1;
                     }
-                    if self.status.score as usize == self.suits.len() * 5 {
-                        self.state = GameState::Won();
+                    self.emit(GameEvent::Played {
+                        player: self.active_player,
+                        pos,
+                        card: card.card,
+                        blind: !card.clued,
+                    });
+                    if self.status.score == self.variant.max_score() {
+                        self.set_state(GameState::Won());
                     }
                     true
                 } else {
-                    if self.debug {
-                        println!(
-                            "Player {} failed to played {:?} from pos {}",
-                            self.active_player, card, pos
-                        );
-                    }
                     self.discard(card.card);
                     self.status.num_strikes += 1;
+                    self.misplays.push(Misplay {
+                        turn: self.status.turn,
+                        player: self.active_player as u8,
+                        pos,
+                        card: card.card,
+                        report: strategies[self.active_player]
+                            .explain_misplay(pos as usize, card.card),
+                    });
+                    self.emit(GameEvent::Misplayed {
+                        player: self.active_player,
+                        pos,
+                        card: card.card,
+                    });
+                    self.emit(GameEvent::Strike {
+                        num_strikes: self.status.num_strikes,
+                    });
                     if self.status.num_strikes == 3 {
-                        self.state = GameState::Lost();
                         for card in self.deck.iter() {
                             self.replay.deck.push(HanabiLiveCard {
                                 rank: card.rank,
-                                suit_index: self.suits.iter().position(|&s| s == card.suit).unwrap()
-                                    as u8,
+                                suit_index: self.variant.suit_index(&card.suit) as u8,
                             })
                         }
                         self.replay.actions.push(HanabiLiveAction {
@@ -818,9 +2073,7 @@ impl Game {
                             target: self.active_player as u8,
                             value: Some(2), // strikeout
                         });
-                        if self.debug {
-                            println!("Game lost due to three strikes");
-                        }
+                        self.set_state(GameState::Lost());
                         return;
                     }
                     false
@@ -836,29 +2089,11 @@ impl Game {
                 self.draw_card(self.active_player, strategies);
             }
             Move::Clue(player, clue) => {
-                if player >= self.hands.len() as u8 || player == 0 {
-                    println!(
-                        "Invalid move: player {} tried to clue to invalid player number {}",
-                        self.active_player, player,
-                    );
-                    self.invalidate_game();
-                    return;
-                }
-                if self.status.clues == 0 {
-                    println!(
-                        "Invalid move: player {} tried to clue but no clue tokens are left",
-                        self.active_player,
-                    );
-                    self.invalidate_game();
-                    return;
-                }
                 let player_index = (self.active_player + player as usize) % self.hands.len();
-                let mut affected_cards = 0;
                 let mut touched = PositionSet::new(self.hands[player_index].len() as u8);
 
                 for (pos, card_state) in self.hands[player_index].iter_mut().enumerate() {
-                    if card_state.clue(clue) {
-                        affected_cards += 1;
+                    if card_state.clue(&self.variant, clue) {
                         touched.add(pos as u8);
                     }
                 }
@@ -874,18 +2109,10 @@ impl Game {
                         self.replay.actions.push(HanabiLiveAction {
                             action: 2,
                             target: player_index as u8,
-                            value: Some(
-                                self.suits.iter().position(|&s| s == color.suit()).unwrap() as u8,
-                            ),
+                            value: Some(self.variant.clue_color_index(&color) as u8),
                         });
                     }
                 }
-                if self.debug {
-                    println!(
-                        "Player {} clue played {} about {} {:?} cards",
-                        self.active_player, player_index, affected_cards, clue
-                    );
-                }
                 for (notify_player, strategy) in strategies.iter_mut().enumerate() {
                     strategy.clued(
                         self.relative_player_index(self.active_player, notify_player),
@@ -895,43 +2122,125 @@ impl Game {
                     );
                 }
                 self.status.clues -= 1;
+                self.emit(GameEvent::Clued {
+                    giver: self.active_player,
+                    receiver: player_index,
+                    clue,
+                    touched,
+                });
             }
+            Move::Resign() => unreachable!("resigning ends the game without a turn"),
         }
         self.active_player = (self.active_player + 1) % self.hands.len();
         self.score_integral += self.status.score as u16;
+        if let GameState::Final(remaining) = previous_state
+            && self.state == previous_state
+        {
+            if remaining > 1 {
+                self.set_state(GameState::Final(remaining - 1));
+            } else {
+                self.replay.actions.push(HanabiLiveAction {
+                    action: 4,
+                    target: self.active_player as u8,
+                    value: Some(1), // normal end
+                });
+                if self.status.score == self.variant.max_score() {
+                    self.set_state(GameState::Won())
+                } else {
+                    self.set_state(GameState::Finished())
+                }
+            }
+        }
+    }
+
+    /// Ends a running game early, e.g. because `player` resigned
+    ///
+    /// The end condition is recorded as on hanab.live ([`END_TERMINATED`] for
+    /// games stopped by the players).
+    pub fn terminate(&mut self, player: u8, end_condition: u8) {
+        if self.is_over() {
+            return;
+        }
+        self.end_early(player, end_condition, GameState::Terminated(end_condition));
     }
 
     fn invalidate_game(&mut self) {
+        self.end_early(self.active_player as u8, 3, GameState::Invalid()); // time out
+    }
+
+    fn end_early(&mut self, player: u8, end_condition: u8, state: GameState) {
         self.replay.actions.push(HanabiLiveAction {
             action: 4,
-            target: self.active_player as u8,
-            value: Some(3), // time out
+            target: player,
+            value: Some(end_condition),
         });
-        self.state = GameState::Invalid();
+        self.set_state(state);
         for card in self.deck.iter() {
             self.replay.deck.push(HanabiLiveCard {
                 rank: card.rank,
-                suit_index: self.suits.iter().position(|&s| s == card.suit).unwrap() as u8,
+                suit_index: self.variant.suit_index(&card.suit) as u8,
             })
         }
     }
 
+    /// Names of the players in replays
+    pub fn set_player_names(&mut self, names: Vec<String>) {
+        self.replay.players = names;
+    }
+
+    fn set_strategy_names(&mut self, strategies: &[&mut dyn PlayerStrategy]) {
+        self.set_player_names(
+            strategies
+                .iter()
+                .enumerate()
+                .map(|(pos, strategy)| format!("{} {}", strategy.name(), pos + 1))
+                .collect(),
+        );
+    }
+
     pub fn print_replay(&self) {
-        let serialized = serde_json::to_string(&self.replay).unwrap();
-        println!("replay JSON: {}", serialized);
-        println!("Replay url: {}", self.replay_url());
+        if let Some(serialized) = self.replay_json() {
+            println!("replay JSON: {}", serialized);
+        }
+        if let Some(url) = self.replay_url() {
+            println!("Replay url: {}", url);
+        }
+    }
+
+    /// The game in the JSON format of hanab.live (not for custom start positions)
+    pub fn replay_json(&self) -> Option<String> {
+        if self.custom_position {
+            return None;
+        }
+        Some(serde_json::to_string(&self.replay).expect("replay should be serializable"))
     }
 
-    pub fn replay_url(&self) -> String {
+    /// Writes the game as hanab.live JSON file
+    pub fn write_replay(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let json = self.replay_json().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "games from custom positions can not be exported",
+            )
+        })?;
+        std::fs::write(path, json)
+    }
+
+    /// Shareable hanab.live replay URL (only for variants with a known hanab.live id)
+    ///
+    /// `None` if hanab.live can't show the game (unknown variant or custom start position).
+    pub fn replay_url(&self) -> Option<String> {
+        if self.custom_position {
+            return None;
+        }
+        let variant_id = self.variant.hanabi_live_id()?;
         let prefix = "https://hanab.live/replay-json/";
         let mut encoded = String::with_capacity(
             // 2 comma, num players, min+max+desk, min+max+actions, + variant
             prefix.len() + 2 + 1 + 2 + self.replay.deck.len() + 2 + self.replay.actions.len() + 1,
         );
         encoded.push_str(prefix);
-        let base62: Vec<char> = "abcdefghijklmnopqrstuvwxyz0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"
-            .chars()
-            .collect();
+        let base62: Vec<char> = BASE62_CHARS.chars().collect();
         // add number of players
         encoded.push_str(&self.num_players().to_string());
         // encode deck
@@ -955,7 +2264,40 @@ impl Game {
         }
         encoded.push(',');
         // add variant id
-        encoded.push('0');
-        encoded
+        encoded.push_str(&variant_id.to_string());
+        encoded.push_str(&self.options.url_flags());
+        Some(encoded)
+    }
+}
+
+#[cfg(test)]
+mod options_tests {
+    use super::*;
+
+    #[test]
+    fn hand_sizes() {
+        let mut options = GameOptions::default();
+        assert_eq!(options.hand_size(2), 5);
+        assert_eq!(options.hand_size(5), 4);
+        options.one_less_card = true;
+        assert_eq!(options.hand_size(6), 2);
+    }
+
+    #[test]
+    fn hanabi_live_json_options() {
+        let parsed: HanabiLiveOptions =
+            serde_json::from_str(r#"{"variant":"No Variant","emptyClues":true}"#).unwrap();
+        assert_eq!(parsed.variant, "No Variant");
+        assert!(parsed.options.empty_clues);
+        assert!(!parsed.options.deck_plays);
+        let serialized = serde_json::to_string(&HanabiLiveOptions {
+            variant: "6 Suits".to_string(),
+            options: GameOptions {
+                one_extra_card: true,
+                ..Default::default()
+            },
+        })
+        .unwrap();
+        assert!(serialized.contains(r#""oneExtraCard":true"#));
     }
 }
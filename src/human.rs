@@ -0,0 +1,340 @@
+use std::io::{self, BufRead, Write};
+
+use colored::Colorize;
+
+use crate::card_quantum::Variant;
+use crate::game::{self, Clue, Move};
+
+/// Strategy for a human sitting at the terminal
+///
+/// Every turn the visible game state is rendered and a move is read with a
+/// small command syntax (slots are numbered from 1, left being the newest card):
+///
+/// - `p <slot>`: play a card
+/// - `d <slot>`: discard a card
+/// - `c <player> <clue>`: clue the player (1 is the next one) with a rank or
+///   a colour character (e.g. `r`, `y`, `3`)
+/// - `q`: give up (an invalid move ends the game)
+pub struct HumanPlayer {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    num_players: u8,
+    own_index: u8,
+    variant: Variant,
+    options: game::GameOptions,
+}
+
+impl std::fmt::Debug for HumanPlayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Human")
+    }
+}
+
+impl Default for HumanPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HumanPlayer {
+    /// Plays via stdin and stdout
+    pub fn new() -> Self {
+        Self::with_io(io::BufReader::new(io::stdin()), io::stdout())
+    }
+
+    pub fn with_io(input: impl BufRead + 'static, output: impl Write + 'static) -> Self {
+        Self {
+            input: Box::new(input),
+            output: Box::new(output),
+            num_players: 0,
+            own_index: 0,
+            variant: Variant::default(),
+            options: game::GameOptions::default(),
+        }
+    }
+
+    /// Name of a player given relative to ourself
+    fn player_name(&self, player: usize) -> String {
+        if player == 0 {
+            "you".to_string()
+        } else {
+            format!(
+                "player {}",
+                (self.own_index as usize + player) % self.num_players as usize + 1
+            )
+        }
+    }
+
+    fn clue_name(clue: Clue) -> String {
+        match clue {
+            Clue::Color(color) => {
+                let suit = color.suit();
+                suit.char().to_string().color(suit.color()).to_string()
+            }
+            Clue::Rank(rank) => rank.to_string(),
+        }
+    }
+
+    fn say(&mut self, message: &str) {
+        // a broken terminal only hides information, the game goes on
+        let _ = writeln!(self.output, "{message}");
+    }
+
+    fn render(&mut self, view: &game::PlayerView) {
+        let status = view.status;
+        let stacks: Vec<String> = self
+            .variant
+            .suits()
+            .iter()
+            .zip(view.played)
+            .zip(view.play_orders)
+            .map(|((suit, &played), order)| match played {
+                0 => format!("{}-", suit.char()).color(suit.color()).to_string(),
+                _ => format!(
+                    "{:?}",
+                    game::Card {
+                        suit: *suit,
+                        rank: order.rank(played - 1).unwrap_or(5),
+                    }
+                ),
+            })
+            .collect();
+        let mut discarded = Vec::new();
+        for (card, &count) in view.discarded {
+            for _ in 0..count {
+                discarded.push(format!("{card:?}"));
+            }
+        }
+        let mut lines = vec![
+            String::new(),
+            format!(
+                "turn {}  score {}/{}  clues {}  strikes {}  deck {}",
+                status.turn,
+                status.score,
+                status.max_score,
+                status.clues,
+                status.num_strikes,
+                view.deck_size
+            ),
+            format!("stacks:  {}", stacks.join(" ")),
+            format!("discard: {}", discarded.join(" ")),
+        ];
+        for (player, hand) in view.hands.iter().enumerate().skip(1) {
+            let cards: Vec<String> = hand
+                .iter()
+                .map(|card| {
+                    let card_name = card
+                        .card
+                        .map(|card| format!("{card:?}"))
+                        .unwrap_or_default();
+                    if card.clued {
+                        format!("{card_name}*")
+                    } else {
+                        format!("{card_name} ")
+                    }
+                })
+                .collect();
+            lines.push(format!(
+                "{:>9}: {}",
+                self.player_name(player),
+                cards.join(" ")
+            ));
+        }
+        let own_cards: Vec<String> = view.hands[0]
+            .iter()
+            .map(|card| {
+                let hints: String = card
+                    .clues
+                    .iter()
+                    .filter(|(_, touched)| *touched)
+                    .map(|(clue, _)| Self::clue_name(*clue))
+                    .collect();
+                if hints.is_empty() {
+                    "? ".to_string()
+                } else {
+                    format!("{hints}*")
+                }
+            })
+            .collect();
+        lines.push(format!("{:>9}: {}", "you", own_cards.join(" ")));
+        for line in lines {
+            self.say(&line);
+        }
+    }
+
+    /// Parses a command into a move that is legal in the given view
+    fn parse_move(&self, input: &str, view: &game::PlayerView) -> Result<Move, String> {
+        let parts: Vec<&str> = input.split_whitespace().collect();
+        let slot = |arg: Option<&&str>| -> Result<u8, String> {
+            match arg.and_then(|arg| arg.parse::<u8>().ok()) {
+                Some(slot) if slot >= 1 => Ok(slot - 1),
+                _ => Err("expected a slot number starting at 1".to_string()),
+            }
+        };
+        let action = match parts.first() {
+            Some(&"p" | &"play") => Move::Play(slot(parts.get(1))?),
+            Some(&"d" | &"discard") => Move::Discard(slot(parts.get(1))?),
+            Some(&"c" | &"clue") => {
+                let player = parts
+                    .get(1)
+                    .and_then(|arg| arg.parse::<u8>().ok())
+                    .ok_or("expected the player to clue (1 is the next player)")?;
+                let clue = parts
+                    .get(2)
+                    .and_then(|arg| self.parse_clue(arg))
+                    .ok_or("expected a rank or colour to clue")?;
+                Move::Clue(player, clue)
+            }
+            _ => {
+                return Err(
+                    "unknown command, use p <slot>, d <slot>, c <player> <clue> or q".into(),
+                );
+            }
+        };
+        let hand_sizes: Vec<u8> = view.hands.iter().map(|hand| hand.len() as u8).collect();
+        let legal_moves = Move::legal_moves(
+            &self.variant,
+            &self.options,
+            view.status.clues,
+            &hand_sizes,
+            |player, clue| {
+                view.hands[player as usize]
+                    .iter()
+                    .filter_map(|card| card.card)
+                    .any(|card| self.variant.touches(&card, clue))
+            },
+        );
+        if legal_moves.contains(&action) {
+            Ok(action)
+        } else {
+            Err(format!("{action:?} is not allowed right now"))
+        }
+    }
+
+    fn parse_clue(&self, arg: &str) -> Option<Clue> {
+        if let Ok(rank) = arg.parse::<u8>() {
+            return Some(Clue::Rank(rank));
+        }
+        let mut chars = arg.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        self.variant
+            .clue_colors()
+            .find(|color| color.suit().char() == c)
+            .map(Clue::Color)
+    }
+}
+
+impl game::PlayerStrategy for HumanPlayer {
+    fn name(&self) -> String {
+        "human".to_string()
+    }
+
+    fn init(
+        &mut self,
+        num_players: u8,
+        own_index: u8,
+        variant: Variant,
+        options: game::GameOptions,
+    ) {
+        self.num_players = num_players;
+        self.own_index = own_index;
+        self.variant = variant;
+        self.options = options;
+        self.say(&format!(
+            "You are player {} of {num_players} ({})",
+            own_index + 1,
+            variant.name()
+        ));
+    }
+
+    fn act(&mut self, view: &game::PlayerView) -> Move {
+        self.render(view);
+        loop {
+            let _ = write!(self.output, "> ");
+            let _ = self.output.flush();
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => line = "q".to_string(),
+                Ok(_) => {}
+            }
+            if matches!(line.trim(), "q" | "quit") {
+                self.say("Giving up");
+                return Move::Resign();
+            }
+            match self.parse_move(&line, view) {
+                Ok(action) => return action,
+                Err(error) => self.say(&error),
+            }
+        }
+    }
+
+    fn drawn(&mut self, _player: usize, _card: game::Card) {}
+    fn own_drawn(&mut self) {}
+
+    fn played(&mut self, player: usize, pos: usize, card: game::Card, successful: bool) {
+        let verb = if successful { "played" } else { "misplayed" };
+        self.say(&format!(
+            "{} {verb} {card:?} from slot {}",
+            self.player_name(player),
+            pos + 1
+        ));
+    }
+
+    fn discarded(&mut self, player: usize, pos: usize, card: game::Card) {
+        self.say(&format!(
+            "{} discarded {card:?} from slot {}",
+            self.player_name(player),
+            pos + 1
+        ));
+    }
+
+    fn clued(&mut self, who: usize, whom: usize, clue: Clue, touched: crate::PositionSet) {
+        let slots: Vec<String> = touched.iter().map(|pos| (pos + 1).to_string()).collect();
+        self.say(&format!(
+            "{} clued {} {} touching slot {}",
+            self.player_name(who),
+            self.player_name(whom),
+            Self::clue_name(clue),
+            slots.join(", ")
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Card, Game, GameBuilder, GameState, Suit};
+    use crate::hyphenated::HyphenatedPlayer;
+
+    #[test]
+    fn plays_commands_from_input() {
+        let suits = [
+            Suit::Red(),
+            Suit::Yellow(),
+            Suit::Green(),
+            Suit::Blue(),
+            Suit::Purple(),
+        ];
+        let deck: Vec<Card> = (1..=5)
+            .flat_map(|rank| suits.map(|suit| Card { suit, rank }))
+            .collect();
+        let input = "x\nd 1\np 9\nc 1 k\nc 1 r\nq\n";
+        let mut human = HumanPlayer::with_io(io::Cursor::new(input), io::sink());
+        let mut bot = HyphenatedPlayer::new(false);
+        let mut players: Vec<&mut dyn game::PlayerStrategy> = vec![&mut human, &mut bot];
+        let mut game: Game = GameBuilder::new(deck)
+            .build(&mut players)
+            .expect("valid setup");
+        // unknown command, discard at 8 clues, missing slot and unknown colour are retried
+        assert_eq!(
+            game.step(&mut players),
+            Some(Move::Clue(1, Clue::Color(game::ClueColor::Red())))
+        );
+        game.step(&mut players);
+        assert_eq!(game.step(&mut players), Some(Move::Resign()));
+        assert_eq!(game.state, GameState::Terminated(game::END_TERMINATED));
+        assert_eq!(game.invalid_move, None);
+    }
+}
@@ -50,8 +50,10 @@ impl std::fmt::Debug for CardState {
 #[derive(PartialEq, Eq, Clone)]
 pub struct CardStates {
     variant: Variant,
-    states: [CardState; 25],
-    first_one_discard: [bool; 5],
+    states: [CardState; 30],
+    discard_counts: [u8; 30],
+    played: [u8; 6],
+    play_orders: [game::PlayOrder; 6],
     pub trash_quantum: CardQuantum,
     pub play_quantum: CardQuantum,
 }
@@ -68,99 +70,116 @@ impl std::fmt::Debug for CardStates {
 }
 
 impl CardStates {
-    pub fn new() -> Self {
-        let v = Variant {};
-        let mut states = [CardState::new(); 25];
-        for suit in v.suits().iter() {
-            states[v.suit_index(suit) * 5].play = CardPlayState::Playable();
-            states[v.suit_index(suit) * 5 + 5 - 1].play = CardPlayState::Critical();
-        }
+    pub fn new(v: Variant) -> Self {
         let mut trash_quantum = CardQuantum::new(v);
         trash_quantum.clear();
         let mut play_quantum = CardQuantum::new(v);
-        play_quantum.limit_by_rank(1, true);
-        Self {
+        play_quantum.clear();
+        let mut play_orders = [game::PlayOrder::Up(); 6];
+        for (index, suit) in v.suits().iter().enumerate() {
+            play_orders[index] = v.play_order(suit);
+        }
+        let mut card_states = Self {
             variant: v,
-            states,
-            first_one_discard: [false; 5],
+            states: [CardState::new(); 30],
+            discard_counts: [0; 30],
+            played: [0; 6],
+            play_orders,
             trash_quantum,
             play_quantum,
+        };
+        for suit in v.suits().iter() {
+            card_states.update_suit(*suit);
         }
+        card_states
     }
 
     pub fn played(&mut self, card: &game::Card) {
-        let offset = self.variant.suit_index(&card.suit) * 5;
-        self.states[offset + card.rank as usize - 1].play = CardPlayState::Trash();
-        self.trash_quantum.add_card(card, false);
-        self.play_quantum.remove_card(card, false);
-        if card.rank == 5 {
-            return;
-        }
-        match self.states[offset + card.rank as usize].play {
-            CardPlayState::Normal() => {
-                self.play_quantum.add_card(
-                    &game::Card {
-                        suit: card.suit,
-                        rank: card.rank + 1,
-                    },
-                    false,
-                );
-                self.states[offset + card.rank as usize].play = CardPlayState::Playable()
-            }
-            CardPlayState::Critical() => {
-                self.play_quantum.add_card(
-                    &game::Card {
-                        suit: card.suit,
-                        rank: card.rank + 1,
-                    },
-                    false,
-                );
-                self.states[offset + card.rank as usize].play = CardPlayState::CriticalPlayable()
-            }
-            _ => {}
-        }
+        let index = self.variant.suit_index(&card.suit);
+        self.play_orders[index] = self.play_orders[index].started_with(card.rank);
+        self.played[index] += 1;
+        self.update_suit(card.suit);
     }
 
     pub fn discarded(&mut self, card: &game::Card) {
-        let offset = self.variant.suit_index(&card.suit) * 5;
-        if card.rank == 1 && !self.first_one_discard[self.variant.suit_index(&card.suit)] {
-            self.first_one_discard[self.variant.suit_index(&card.suit)] = true;
-            return;
-        }
-        match self.states[offset + card.rank as usize - 1].play {
-            CardPlayState::Normal() => {
-                self.states[offset + card.rank as usize - 1].play = CardPlayState::Critical()
-            }
-            CardPlayState::Playable() => {
-                self.states[offset + card.rank as usize - 1].play =
-                    CardPlayState::CriticalPlayable()
-            }
-            CardPlayState::Critical() => {
-                for higher_rank in card.rank..=5 {
-                    self.states[offset + higher_rank as usize - 1].play = CardPlayState::Dead();
-                    self.trash_quantum.add_card(
-                        &game::Card {
-                            suit: card.suit,
-                            rank: higher_rank,
-                        },
-                        false,
-                    );
+        self.discard_counts[self.variant.suit_index(&card.suit) * 5 + card.rank as usize - 1] += 1;
+        self.update_suit(card.suit);
+    }
+
+    pub fn play_order(&self, suit: &game::Suit) -> game::PlayOrder {
+        self.play_orders[self.variant.suit_index(suit)]
+    }
+
+    /// The card that becomes playable after the given card was played
+    pub fn next_card(&self, card: &game::Card) -> Option<game::Card> {
+        let order = self.play_order(&card.suit);
+        let rank = order.rank(order.step(card.rank)? + 1)?;
+        Some(game::Card {
+            suit: card.suit,
+            rank,
+        })
+    }
+
+    /// Recalculates the play states of all cards of a suit
+    fn update_suit(&mut self, suit: game::Suit) {
+        let index = self.variant.suit_index(&suit);
+        let order = self.play_orders[index];
+        let played = self.played[index];
+        let remaining = |rank: u8| {
+            let card = game::Card { suit, rank };
+            self.variant
+                .card_count(&card)
+                .saturating_sub(self.discard_counts[index * 5 + rank as usize - 1])
+        };
+        // a card is reachable if no card before it in the play order is lost:
+        let reachable_in = |order: game::PlayOrder, rank: u8| {
+            let Some(step) = order.step(rank) else {
+                return false;
+            };
+            (played..=step).all(|previous_step| {
+                order
+                    .rank(previous_step)
+                    .is_some_and(|previous_rank| remaining(previous_rank) > 0)
+            })
+        };
+        for rank in 1..=5 {
+            let card = game::Card { suit, rank };
+            let reachable = match order {
+                game::PlayOrder::Open() => {
+                    reachable_in(game::PlayOrder::Up(), rank)
+                        || reachable_in(game::PlayOrder::Down(), rank)
                 }
-            }
-            CardPlayState::CriticalPlayable() => {
-                for higher_rank in card.rank..=5 {
-                    self.states[offset + higher_rank as usize - 1].play = CardPlayState::Dead();
-                    self.play_quantum.remove_card(card, false);
-                    self.trash_quantum.add_card(
-                        &game::Card {
-                            suit: card.suit,
-                            rank: higher_rank,
-                        },
-                        false,
-                    );
+                _ => reachable_in(order, rank),
+            };
+            let playable = match order {
+                game::PlayOrder::Open() => rank == 1 || rank == 5,
+                _ => order.rank(played) == Some(rank),
+            };
+            let play = if order.step(rank).is_some_and(|step| step < played) {
+                CardPlayState::Trash()
+            } else if !reachable {
+                CardPlayState::Dead()
+            } else {
+                match (playable, remaining(rank) == 1) {
+                    (true, false) => CardPlayState::Playable(),
+                    (true, true) => CardPlayState::CriticalPlayable(),
+                    (false, false) => CardPlayState::Normal(),
+                    (false, true) => CardPlayState::Critical(),
+                }
+            };
+            self.states[index * 5 + rank as usize - 1].play = play;
+            match play {
+                CardPlayState::Trash() | CardPlayState::Dead() => {
+                    self.trash_quantum.add_card(&card, false);
+                    self.play_quantum.remove_card(&card, false);
+                }
+                CardPlayState::Playable() | CardPlayState::CriticalPlayable() => {
+                    self.play_quantum.add_card(&card, false);
+                }
+                CardPlayState::Normal() | CardPlayState::Critical() => {
+                    self.play_quantum.remove_card(&card, false);
                 }
             }
-            _ => {}
         }
     }
 
@@ -205,7 +224,7 @@ impl<'a> Iterator for CardStateIterator<'a> {
     type Item = (game::Card, &'a CardState);
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.next_pos < 25 {
+        while (self.next_pos as usize) < self.card_states.variant.len() * 5 {
             let card_state = &self.card_states.states[self.next_pos as usize];
             if self.only_clued && card_state.clued.is_none() {
                 self.next_pos += 1;
@@ -224,7 +243,7 @@ impl<'a> Iterator for CardStateIterator<'a> {
 
 impl Default for CardStates {
     fn default() -> Self {
-        Self::new()
+        Self::new(Variant::no_variant())
     }
 }
 
@@ -235,7 +254,7 @@ mod tests {
     #[test]
     fn initial_state() {
         let suit = game::Suit::Red();
-        let p = CardStates::new();
+        let p = CardStates::default();
         assert_eq!(
             p[&game::Card { rank: 1, suit }].play,
             CardPlayState::Playable()
@@ -261,7 +280,7 @@ mod tests {
     #[test]
     fn play_card() {
         let suit = game::Suit::Red();
-        let mut p = CardStates::new();
+        let mut p = CardStates::default();
         assert_eq!(
             p[&game::Card { rank: 2, suit }].play,
             CardPlayState::Normal()
@@ -295,7 +314,7 @@ mod tests {
     #[test]
     fn discard_card() {
         let suit = game::Suit::Blue();
-        let mut p = CardStates::new();
+        let mut p = CardStates::default();
         p.discarded(&game::Card { rank: 3, suit });
         assert_eq!(
             p[&game::Card { rank: 3, suit }].play,
@@ -307,10 +326,109 @@ mod tests {
         assert_eq!(p[&game::Card { rank: 5, suit }].play, CardPlayState::Dead());
     }
 
+    #[test]
+    fn discard_ones() {
+        let suit = game::Suit::Green();
+        let mut p = CardStates::default();
+        p.discarded(&game::Card { rank: 1, suit });
+        assert_eq!(
+            p[&game::Card { rank: 1, suit }].play,
+            CardPlayState::Playable()
+        );
+        p.discarded(&game::Card { rank: 1, suit });
+        assert_eq!(
+            p[&game::Card { rank: 1, suit }].play,
+            CardPlayState::CriticalPlayable()
+        );
+        p.discarded(&game::Card { rank: 1, suit });
+        assert_eq!(p[&game::Card { rank: 1, suit }].play, CardPlayState::Dead());
+        assert_eq!(p[&game::Card { rank: 5, suit }].play, CardPlayState::Dead());
+    }
+
+    #[test]
+    fn dark_suit() {
+        let suit = game::Suit::Black();
+        let mut p = CardStates::new(Variant::from_name("Black (5 Suits)").unwrap());
+        assert_eq!(
+            p[&game::Card { rank: 1, suit }].play,
+            CardPlayState::CriticalPlayable()
+        );
+        for rank in 2..=5 {
+            assert_eq!(
+                p[&game::Card { rank, suit }].play,
+                CardPlayState::Critical()
+            );
+        }
+        p.discarded(&game::Card { rank: 3, suit });
+        assert_eq!(p[&game::Card { rank: 3, suit }].play, CardPlayState::Dead());
+        assert_eq!(p[&game::Card { rank: 5, suit }].play, CardPlayState::Dead());
+        assert_eq!(
+            p[&game::Card { rank: 2, suit }].play,
+            CardPlayState::Critical()
+        );
+    }
+
+    #[test]
+    fn reversed_suit() {
+        let suit = game::Suit::Purple();
+        let mut p = CardStates::new(Variant::from_name("Reversed (5 Suits)").unwrap());
+        assert_eq!(
+            p[&game::Card { rank: 5, suit }].play,
+            CardPlayState::Playable()
+        );
+        assert_eq!(
+            p[&game::Card { rank: 1, suit }].play,
+            CardPlayState::Critical()
+        );
+        p.played(&game::Card { rank: 5, suit });
+        assert_eq!(
+            p[&game::Card { rank: 4, suit }].play,
+            CardPlayState::Playable()
+        );
+        p.discarded(&game::Card { rank: 3, suit });
+        p.discarded(&game::Card { rank: 3, suit });
+        assert_eq!(p[&game::Card { rank: 3, suit }].play, CardPlayState::Dead());
+        assert_eq!(p[&game::Card { rank: 1, suit }].play, CardPlayState::Dead());
+        assert_eq!(
+            p[&game::Card { rank: 4, suit }].play,
+            CardPlayState::Playable()
+        );
+    }
+
+    #[test]
+    fn up_or_down_suit() {
+        let suit = game::Suit::Red();
+        let mut p = CardStates::new(Variant::from_name("Up or Down (5 Suits)").unwrap());
+        for rank in [1, 5] {
+            assert_eq!(
+                p[&game::Card { rank, suit }].play,
+                CardPlayState::CriticalPlayable()
+            );
+        }
+        // a lost 3 can still be reached from both sides:
+        p.discarded(&game::Card { rank: 3, suit });
+        p.discarded(&game::Card { rank: 3, suit });
+        assert_eq!(p[&game::Card { rank: 3, suit }].play, CardPlayState::Dead());
+        assert_eq!(
+            p[&game::Card { rank: 4, suit }].play,
+            CardPlayState::Normal()
+        );
+        p.played(&game::Card { rank: 5, suit });
+        assert_eq!(
+            p[&game::Card { rank: 4, suit }].play,
+            CardPlayState::Playable()
+        );
+        assert_eq!(p[&game::Card { rank: 1, suit }].play, CardPlayState::Dead());
+        assert_eq!(
+            p.next_card(&game::Card { rank: 4, suit }),
+            Some(game::Card { rank: 3, suit })
+        );
+    }
+
     #[test]
     fn play_critical() {
         let suit = game::Suit::Yellow();
-        let mut p = CardStates::new();
+        let mut p = CardStates::default();
         p.discarded(&game::Card { rank: 2, suit });
         assert_eq!(
             p[&game::Card { rank: 2, suit }].play,
@@ -7,6 +7,7 @@ use crate::{
 };
 
 use super::card_states::CardStates;
+use super::misplay::MisplayBelief;
 use super::slot::Slot;
 
 use slog;
@@ -86,6 +87,7 @@ pub struct Line {
     pub hands: Hands,
     turn: i8,
     variant: Variant,
+    options: game::GameOptions,
     pub card_states: CardStates,
     score: u8,
     own_player: u8,
@@ -98,6 +100,7 @@ impl PartialEq for Line {
         self.hands == other.hands
             && self.turn == other.turn
             && self.variant == other.variant
+            && self.options == other.options
             && self.card_states == other.card_states
             && self.score == other.score
             && self.own_player == other.own_player
@@ -106,13 +109,16 @@ impl PartialEq for Line {
 }
 impl Eq for Line {}
 
+/// Enough slots for five players with one extra card each (plus one spare for shifting)
+const MAX_SLOTS: usize = 26;
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct Hands {
     pub num_players: u8,
     pub hand_sizes: [u8; 6],
     max_hand_size: u8,
-    hand_slots: [u8; 20],
-    slots: [Slot; 20],
+    hand_slots: [u8; MAX_SLOTS],
+    slots: [Slot; MAX_SLOTS],
     next_slot: Option<u8>,
     used_slots: u8,
 }
@@ -356,21 +362,24 @@ struct PlayEvaluation {
     card: game::Card,
     places: [(u8, u8, PlayRelation); 5],
     pending_marks: bool,
-    played_rank: u8,
+    order: game::PlayOrder,
+    played_steps: u8,
     marked_cards: [PositionSet; 6],
     logger: slog::Logger,
 }
 
 impl PlayEvaluation {
     fn prep(line: &mut Line, who: usize, whom: usize, pos: u8, logger: slog::Logger) -> u8 {
+        let card = line.hands.slot(whom as u8, pos).card;
         let mut evaluation = Self {
             who,
             whom,
             pos,
-            card: line.hands.slot(whom as u8, pos).card,
+            card,
             places: [(0, 0, PlayRelation::Normal()); 5],
             pending_marks: false,
-            played_rank: 0,
+            order: line.card_states.play_order(&card.suit),
+            played_steps: 0,
             marked_cards: [PositionSet::new(6); 6],
             logger,
         };
@@ -405,7 +414,8 @@ impl PlayEvaluation {
             card,
             places: [(0, 0, PlayRelation::Normal()); 5],
             pending_marks: false,
-            played_rank: 0,
+            order: line.card_states.play_order(&card.suit),
+            played_steps: 0,
             marked_cards: [PositionSet::new(6); 6],
             logger,
         };
@@ -415,23 +425,6 @@ impl PlayEvaluation {
         }
     }
 
-    fn empty() -> Self {
-        PlayEvaluation {
-            who: 0,
-            whom: 0,
-            pos: 0,
-            card: game::Card {
-                suit: game::Suit::Red(),
-                rank: 0,
-            },
-            places: [(0, 0, PlayRelation::Normal()); 5],
-            pending_marks: false,
-            played_rank: 0,
-            marked_cards: [PositionSet::new(6); 6],
-            logger: slog::Logger::root(slog::Discard, slog::o!()),
-        }
-    }
-
     fn find_clued_card(
         &mut self,
         line: &mut Line,
@@ -565,8 +558,16 @@ impl PlayEvaluation {
             game::CardPlayState::Trash() => Err(true),
             game::CardPlayState::Dead() => Err(true),
             _ => {
+                let Some(card_step) = self.order.step(self.card.rank) else {
+                    // the play order is still open: only its first card (1 or 5) can be played
+                    return Err(false);
+                };
                 let mut all_connecting_cards = true;
-                'rank_loop: for previous_rank in 1..self.card.rank {
+                'rank_loop: for previous_step in 0..card_step {
+                    let previous_rank = self
+                        .order
+                        .rank(previous_step)
+                        .expect("previous steps are part of the play order");
                     let previous_card = game::Card {
                         rank: previous_rank,
                         suit: self.card.suit,
@@ -574,7 +575,7 @@ impl PlayEvaluation {
                     let previous_state = line.card_states[&previous_card];
                     if previous_state.play == game::CardPlayState::Trash() {
                         // card already played
-                        self.played_rank = previous_rank;
+                        self.played_steps = previous_step + 1;
                         continue;
                     }
                     // obvious place: everybody knows where it is
@@ -781,13 +782,19 @@ impl PlayEvaluation {
             return;
         }
         slog::debug!(self.logger, "mark: {self:?}");
-        for previous_rank in (self.played_rank + 1)..self.card.rank {
+        let card_step = self
+            .order
+            .step(self.card.rank)
+            .expect("marked cards have a decided play order");
+        for previous_step in self.played_steps..card_step {
+            let previous_rank = self.order.rank(previous_step).expect("valid step");
+            let next_rank = self.order.rank(previous_step + 1).expect("valid step");
             let previous_card = game::Card {
                 rank: previous_rank,
                 suit: self.card.suit,
             };
             let (player, pos, relation) = self.places[previous_rank as usize - 1];
-            let (next_player, next_pos, _) = self.places[previous_rank as usize];
+            let (next_player, next_pos, _) = self.places[next_rank as usize - 1];
             let found_slot = line.hands.slot_mut(player, pos);
             match relation {
                 PlayRelation::Normal() => {
@@ -815,7 +822,7 @@ impl PlayEvaluation {
                             delayed_slot: line.hands.slot_index(next_player, next_pos),
                             pending_slot: line.hands.slot_index(player, pos),
                         });
-                        line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                        line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                     }
                     if certainty == MarkCertainty::Prep() {
                         let focused_slot = line.hands.slot_mut(self.whom as u8, self.pos);
@@ -834,7 +841,7 @@ impl PlayEvaluation {
                                 delayed_slot: line.hands.slot_index(next_player, next_pos),
                                 pending_slot: line.hands.slot_index(player, pos),
                             });
-                            line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                            line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                         }
                         if player != self.whom as u8 {
                             let focused_slot = line.hands.slot_mut(self.whom as u8, self.pos);
@@ -851,7 +858,7 @@ impl PlayEvaluation {
                                 delayed_slot: line.hands.slot_index(next_player, next_pos),
                                 pending_slot: line.hands.slot_index(player, pos),
                             });
-                            line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                            line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                         }
                     }
                     MarkCertainty::Ambigious() => {
@@ -859,7 +866,7 @@ impl PlayEvaluation {
                             delayed_slot: line.hands.slot_index(next_player, next_pos),
                             potential_player: player,
                         });
-                        line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                        line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                     }
                 },
                 PlayRelation::Finess() => match certainty {
@@ -875,7 +882,7 @@ impl PlayEvaluation {
                                 pending_slot: line.hands.slot_index(player, pos),
                                 expected_card: previous_card,
                             });
-                            line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                            line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                         }
                     }
                     MarkCertainty::Unambigious() => {
@@ -889,7 +896,7 @@ impl PlayEvaluation {
                             pending_slot: line.hands.slot_index(player, pos),
                             expected_card: previous_card,
                         });
-                        line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                        line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                     }
                     MarkCertainty::Ambigious() => {
                         if self.whom == 0 || correct {
@@ -904,7 +911,7 @@ impl PlayEvaluation {
                                 pending_slot: line.hands.slot_index(player, pos),
                                 expected_card: previous_card,
                             });
-                            line.hands.slot_mut(next_player, next_pos).delayed += 1;
+                            line.hands.slot_mut(next_player, next_pos).delay(line.turn);
                         }
                     }
                 },
@@ -914,8 +921,13 @@ impl PlayEvaluation {
 }
 
 impl Line {
-    pub fn with_logger(num_players: u8, own_player: u8, logger: slog::Logger) -> Self {
-        let variant = Variant {};
+    pub fn with_logger(
+        num_players: u8,
+        own_player: u8,
+        variant: Variant,
+        options: game::GameOptions,
+        logger: slog::Logger,
+    ) -> Self {
         let empty_slot = Slot {
             quantum: CardQuantum::new(variant),
             play: false,
@@ -929,6 +941,7 @@ impl Line {
             fixed: false,
             turn: -100,
             delayed: 0,
+            waited: None,
             callbacks: false,
             promised: None,
         };
@@ -936,23 +949,16 @@ impl Line {
             hands: Hands {
                 num_players,
                 hand_sizes: [0; 6],
-                max_hand_size: match num_players {
-                    2 => 5,
-                    3 => 5,
-                    4 => 4,
-                    5 => 4,
-                    6 => 3,
-                    _ => unimplemented!(),
-                },
-
-                hand_slots: [0; 20],
-                slots: [empty_slot; 20],
+                max_hand_size: options.hand_size(num_players),
+                hand_slots: [0; MAX_SLOTS],
+                slots: [empty_slot; MAX_SLOTS],
                 next_slot: None,
                 used_slots: 0,
             },
             turn: -16,
-            variant: Variant {},
-            card_states: CardStates::new(),
+            variant,
+            options,
+            card_states: CardStates::new(variant),
             score: 0,
             own_player,
             callbacks: VecDeque::new(),
@@ -964,6 +970,8 @@ impl Line {
         Self::with_logger(
             num_players,
             own_player,
+            Variant::no_variant(),
+            game::GameOptions::default(),
             slog::Logger::root(slog::Discard, slog::o!()),
         )
     }
@@ -1123,7 +1131,7 @@ impl Line {
     pub fn drawn(&mut self, player: usize, card: game::Card) {
         let mut quantum = CardQuantum::new(self.variant);
         for (card, state) in self.card_states.iter() {
-            if state.tracked_count == card.suit.card_count(card.rank)
+            if state.tracked_count == self.variant.card_count(&card)
                 && !state.tracked_places.contains(&(player as i8))
             {
                 // player sees all instances of this card
@@ -1142,6 +1150,7 @@ impl Line {
                 fixed: false,
                 turn: self.turn,
                 delayed: 0,
+                waited: None,
                 callbacks: false,
                 promised: None,
             },
@@ -1166,6 +1175,7 @@ impl Line {
             fixed: false,
             turn: self.turn,
             delayed: 0,
+            waited: None,
             callbacks: false,
             promised: None,
         };
@@ -1173,7 +1183,7 @@ impl Line {
             self.turn += 1;
         }
         for (card, state) in self.card_states.iter() {
-            if state.tracked_count == card.suit.card_count(card.rank) {
+            if state.tracked_count == self.variant.card_count(&card) {
                 hand.quantum.remove_card(&card, false);
             }
         }
@@ -1208,8 +1218,20 @@ impl Line {
         // fix also other hands?
     }
 
-    pub fn played(&mut self, player: usize, pos: usize, card: game::Card, successful: bool) {
+    /// Advances the turn, remembering which slots are still waiting
+    fn next_turn(&mut self) {
         self.turn += 1;
+        for player in 0..self.hands.num_players {
+            for (_pos, slot) in self.hands.iter_hand_mut(player) {
+                if slot.delayed > 0 {
+                    slot.waited = Some(self.turn);
+                }
+            }
+        }
+    }
+
+    pub fn played(&mut self, player: usize, pos: usize, card: game::Card, successful: bool) {
+        self.next_turn();
         let slot_index = self.hands.remove_slot(player, pos as u8);
         let slot = self.hands.slots[slot_index];
         if player == 0 {
@@ -1319,11 +1341,9 @@ impl Line {
                         } else {
                             let slot = &mut self.hands.slots[delayed_slot as usize];
                             slot.delayed -= 1;
-                            let next_card = game::Card {
-                                rank: card.rank + 1,
-                                suit: card.suit,
-                            };
-                            if slot.quantum.contains_hard(&next_card) {
+                            if let Some(next_card) = self.card_states.next_card(&card)
+                                && slot.quantum.contains_hard(&next_card)
+                            {
                                 slot.quantum.add_card(&next_card, true);
                             }
                             if slot.delayed == 0 {
@@ -1363,11 +1383,9 @@ impl Line {
                         let slot = &mut self.hands.slots[delayed_slot as usize];
                         if card == expected_card {
                             slot.delayed -= 1;
-                            let next_card = game::Card {
-                                rank: card.rank + 1,
-                                suit: card.suit,
-                            };
-                            if slot.quantum.contains_hard(&next_card) && card.rank < 5 {
+                            if let Some(next_card) = self.card_states.next_card(&card)
+                                && slot.quantum.contains_hard(&next_card)
+                            {
                                 slot.delayed = 0;
                                 slot.update_slot_attributes(&self.card_states);
                                 slot.quantum.soft_clear();
@@ -1478,7 +1496,7 @@ impl Line {
     }
 
     pub fn discarded(&mut self, player: usize, pos: usize, card: game::Card) {
-        self.turn += 1;
+        self.next_turn();
         self.card_states.discarded(&card);
         let slot_index = self.hands.remove_slot(player, pos as u8);
         if self.hands.slots[slot_index].clued && player > 0 {
@@ -1574,7 +1592,7 @@ impl Line {
             state.tracked_count += 1
         }
         let state = &self.card_states[&card];
-        if state.tracked_count == card.suit.card_count(card.rank) {
+        if state.tracked_count == self.variant.card_count(&card) {
             // all instances of card are tracked (elsewhere!), update card quantum accordingly
             for player in 0..self.hands.num_players {
                 if !state.tracked_places.contains(&(player as i8)) {
@@ -1597,7 +1615,7 @@ impl Line {
         clue: game::Clue,
         touched: game::PositionSet,
     ) -> u8 {
-        self.turn += 1;
+        self.next_turn();
         for i in (0..self.callbacks.len()).rev() {
             if let Callback::PotentialPrompt {
                 delayed_slot,
@@ -1622,14 +1640,7 @@ impl Line {
             }
             let old_size = slot.quantum.size();
             let previsous_first_quantum_card = slot.quantum.iter().next();
-            match clue {
-                game::Clue::Rank(rank) => slot
-                    .quantum
-                    .limit_by_rank(rank as usize, touched.contains(pos)),
-                game::Clue::Color(color) => slot
-                    .quantum
-                    .limit_by_suit(&color.suit(), touched.contains(pos)),
-            }
+            slot.quantum.limit_by_clue(clue, touched.contains(pos));
             if old_size != 0 && slot.quantum.size() == 0 && slot.quantum.hard_size() == 1 {
                 slot.quantum.reset_soft();
                 if old_size == 1 {
@@ -1664,8 +1675,15 @@ impl Line {
                 }
             }
         }
+        if touched.is_empty() {
+            // empty clue (only allowed with the empty clues option): there is no focus,
+            // it only carries the negative information applied above and gives no tempo
+            return error;
+        }
         if newly_clued.is_empty() {
-            let focus = touched.first().expect("empty clues are not implemented");
+            let focus = touched
+                .first()
+                .expect("We have checked previously that touched must contain something");
             let slot = self.hands.slot_mut(whom as u8, focus);
             if slot.play && !slot.locked && !slot.fixed {
                 // useless reclue
@@ -1691,8 +1709,13 @@ impl Line {
             for potential_card in chop_slot.quantum.clone().iter() {
                 match self.card_states[&potential_card].play {
                     game::CardPlayState::Critical() => {
-                        if potential_card.rank == 5 && clue != game::Clue::Rank(5) {
-                            // 5s will only be safed via rank
+                        if potential_card.rank == 5
+                            && clue != game::Clue::Rank(5)
+                            && self.variant.touches(&potential_card, game::Clue::Rank(5))
+                            && !potential_card.suit.is_dark()
+                        {
+                            // 5s will only be safed via rank (if they can be rank clued
+                            // and are not the only copy of their suit)
                         } else {
                             potential_safe = true
                         }
@@ -1703,10 +1726,10 @@ impl Line {
                     game::CardPlayState::Trash() => {
                         chop_slot.quantum.remove_card(&potential_card, true);
                     }
-                    game::CardPlayState::Normal() => {
-                        if potential_card.rank == 2 && clue == game::Clue::Rank(2) {
-                            potential_safe = true;
-                        }
+                    game::CardPlayState::Normal()
+                        if Self::is_two_save(&self.variant, &potential_card, clue) =>
+                    {
+                        potential_safe = true;
                     }
                     _ => {}
                 }
@@ -1715,7 +1738,7 @@ impl Line {
         } else {
             touched
                 .first()
-                .expect("We have check previously that touched must contain something")
+                .expect("We have checked previously that touched must contain something")
         };
 
         // somebody else was clued -> remember which cards are clued
@@ -1730,12 +1753,16 @@ impl Line {
             }
             if pos == focus {
                 if potential_safe
-                    && (whom == 0 || !(slot.card.rank == 5 && clue != game::Clue::Rank(5)))
+                    && (whom == 0
+                        || !(slot.card.rank == 5
+                            && clue != game::Clue::Rank(5)
+                            && self.variant.touches(&slot.card, game::Clue::Rank(5))
+                            && !slot.card.suit.is_dark()))
                 {
                     for potential_card in slot.quantum.clone().iter() {
                         match self.card_states[&potential_card].play {
                             game::CardPlayState::Normal() => {
-                                if potential_card.rank == 2 && clue == game::Clue::Rank(2) {
+                                if Self::is_two_save(&self.variant, &potential_card, clue) {
                                     let mut second_copy_visible_by_both = false;
                                     for i in 0..self.card_states[&potential_card].tracked_count {
                                         let place = self.card_states[&potential_card]
@@ -1758,11 +1785,16 @@ impl Line {
                             game::CardPlayState::Trash() => {
                                 slot.quantum.remove_card(&potential_card, true);
                             }
-                            game::CardPlayState::Critical() => {
-                                if potential_card.rank == 5 && clue != game::Clue::Rank(5) {
-                                    slot.quantum.remove_card(&potential_card, true);
-                                    // 5 will only be safed via rank
-                                }
+                            game::CardPlayState::Critical()
+                                if potential_card.rank == 5
+                                    && clue != game::Clue::Rank(5)
+                                    && self
+                                        .variant
+                                        .touches(&potential_card, game::Clue::Rank(5))
+                                    && !potential_card.suit.is_dark() =>
+                            {
+                                slot.quantum.remove_card(&potential_card, true);
+                                // 5 will only be safed via rank
                             }
                             _ => {}
                         }
@@ -1855,14 +1887,8 @@ impl Line {
                 }
             }
         }
-        let mut evaluations: [PlayEvaluation; 5] = [
-            PlayEvaluation::empty(),
-            PlayEvaluation::empty(),
-            PlayEvaluation::empty(),
-            PlayEvaluation::empty(),
-            PlayEvaluation::empty(),
-        ];
-        let mut num_evaluations = 0;
+        // multi-suit clues (e.g. rainbow) can leave more than one candidate per suit
+        let mut evaluations: Vec<PlayEvaluation> = Vec::with_capacity(5);
         // 0. update prompt based on actually clued card:
         if whom > 0 {
             error += PlayEvaluation::prep(
@@ -1890,10 +1916,7 @@ impl Line {
                     slog::o!("eval" => "non-self", "card?" => format!("{:?}", potential_card)),
                 ),
             ) {
-                Ok(evaluation) => {
-                    evaluations[num_evaluations] = evaluation;
-                    num_evaluations += 1;
-                }
+                Ok(evaluation) => evaluations.push(evaluation),
                 Err(soft) => {
                     self.hands
                         .slot_mut(whom as u8, pos)
@@ -1920,10 +1943,7 @@ impl Line {
                         slog::o!("eval" => "self-prompt", "card?" => format!("{:?}", potential_card)),
                     ),
                 ) {
-                    Ok(evaluation) => {
-                        evaluations[num_evaluations] = evaluation;
-                        num_evaluations += 1;
-                    }
+                    Ok(evaluation) => evaluations.push(evaluation),
                     Err(soft) => {
                         self.hands
                             .slot_mut(whom as u8, pos)
@@ -1951,10 +1971,7 @@ impl Line {
                         slog::o!("eval" => "self-finess", "card?" => format!("{:?}", potential_card)),
                     ),
                 ) {
-                    Ok(evaluation) => {
-                        evaluations[num_evaluations] = evaluation;
-                        num_evaluations += 1;
-                    }
+                    Ok(evaluation) => evaluations.push(evaluation),
                     Err(soft) => {
                         self.hands
                             .slot_mut(whom as u8, pos)
@@ -1974,7 +1991,7 @@ impl Line {
                     "Play evaluation left only: {:?}",
                     evaluations[0].card
                 );
-                assert_eq!(num_evaluations, 1);
+                assert_eq!(evaluations.len(), 1);
                 self.hands
                     .slot_mut(whom as u8, pos)
                     .quantum
@@ -2002,17 +2019,32 @@ impl Line {
     pub fn clue(&mut self, whom: usize, clue: game::Clue) -> Option<LineScore> {
         let mut touched = PositionSet::new(self.hands.hand_sizes[whom]);
         for (pos, slot) in self.hands.iter_hand_mut(whom as u8) {
-            if slot.card.affected(clue) {
+            if self.variant.touches(&slot.card, clue) {
                 touched.add(pos);
             }
         }
-        if touched.is_empty() {
+        if touched.is_empty() && !self.options.empty_clues {
             return None;
         }
         let error = self.clued(0, whom, clue, touched);
         Some(self.score(error))
     }
 
+    /// Moves the own player is allowed to make, as far as they can tell
+    pub fn legal_moves(&self, clues: u8) -> Vec<game::Move> {
+        game::Move::legal_moves(
+            &self.variant,
+            &self.options,
+            clues,
+            &self.hands.hand_sizes[..self.hands.num_players as usize],
+            |player, clue| {
+                self.hands
+                    .iter_hand(player)
+                    .any(|(_pos, slot)| self.variant.touches(&slot.card, clue))
+            },
+        )
+    }
+
     pub fn discard(&mut self) -> game::Move {
         // look for trash
         let mut chop = -1;
@@ -2059,6 +2091,44 @@ impl Line {
         None
     }
 
+    /// Whether the clue saves the card as a 2 save: a rank clue on the second card
+    /// of its suit (the 4 of reversed suits)
+    fn is_two_save(variant: &Variant, card: &game::Card, clue: game::Clue) -> bool {
+        let second_rank = match variant.play_order(&card.suit) {
+            game::PlayOrder::Open() => 2,
+            order => order.rank(1).expect("suits have more than one card"),
+        };
+        card.rank == second_rank && clue == game::Clue::Rank(second_rank)
+    }
+
+    /// Captures what we know about our card at `pos` (before it is removed)
+    pub fn misplay_belief(&self, pos: u8, card: game::Card) -> MisplayBelief {
+        let slot_index = self.hands.slot_index(0, pos);
+        let slot = *self.hands.slot(0, pos);
+        let pending = self
+            .callbacks
+            .iter()
+            .filter(|callback| match callback {
+                Callback::WaitingPlay { pending_slot, .. }
+                | Callback::PotentialFiness { pending_slot, .. }
+                | Callback::Finess { pending_slot, .. } => *pending_slot == slot_index,
+                Callback::PotentialPrompt { .. } => false,
+            })
+            .cloned()
+            .collect();
+        MisplayBelief {
+            card,
+            slot,
+            pending,
+            surely_playable: self.card_states.play_quantum.superset(slot.quantum),
+            // released delays only explain plays within a round
+            delayed: slot.delayed > 0
+                || slot
+                    .waited
+                    .is_some_and(|turn| self.turn - turn <= self.hands.num_players as i8),
+        }
+    }
+
     pub fn print_callbacks(&self, prefix: &str) {
         let mut output = prefix.to_string();
         for (pos, callback) in self.callbacks.iter().enumerate() {
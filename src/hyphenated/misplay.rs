@@ -0,0 +1,135 @@
+use crate::game::{self, MisplayCause};
+
+use super::line::Callback;
+use super::slot::Slot;
+
+/// What the player knew about an own card when it turned out to be unplayable
+#[derive(Clone, Debug)]
+pub struct MisplayBelief {
+    pub card: game::Card,
+    pub slot: Slot,
+    /// callbacks waiting for the card to be played
+    pub pending: Vec<Callback>,
+    /// whether all options of the card were playable
+    pub surely_playable: bool,
+    /// whether the slot was still waiting for other cards, or stopped waiting within the last round
+    pub delayed: bool,
+}
+
+impl MisplayBelief {
+    pub fn cause(&self) -> MisplayCause {
+        let finessed = self.pending.iter().any(|callback| {
+            matches!(
+                callback,
+                Callback::Finess { .. } | Callback::PotentialFiness { .. }
+            )
+        });
+        let prompted = self
+            .pending
+            .iter()
+            .any(|callback| matches!(callback, Callback::WaitingPlay { .. }));
+        if self.slot.promised.is_some() || finessed {
+            MisplayCause::WrongFinesse()
+        } else if prompted {
+            MisplayCause::WrongPrompt()
+        } else if self.delayed {
+            MisplayCause::DelayedPlay()
+        } else if !self.surely_playable {
+            MisplayCause::StalePlayFlag()
+        } else if !self.slot.quantum.contains(&self.card) {
+            MisplayCause::WrongInference()
+        } else {
+            MisplayCause::Unexplained()
+        }
+    }
+
+    pub fn report(&self) -> game::MisplayReport {
+        game::MisplayReport {
+            cause: self.cause(),
+            belief: format!("{self}"),
+        }
+    }
+}
+
+impl std::fmt::Display for MisplayBelief {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} was {:?}", self.card, self.slot)?;
+        if self.delayed {
+            f.write_str(" after waiting")?;
+        }
+        for callback in &self.pending {
+            write!(f, ", {callback:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_quantum::{CardQuantum, Variant};
+
+    #[test]
+    fn classify_misplays() {
+        let red_two = game::Card {
+            suit: game::Suit::Red(),
+            rank: 2,
+        };
+        let mut quantum = CardQuantum::new(Variant::no_variant());
+        quantum.clear();
+        quantum.add_card(
+            &game::Card {
+                suit: game::Suit::Blue(),
+                rank: 1,
+            },
+            false,
+        );
+        let slot = Slot {
+            card: game::Card {
+                suit: game::Suit::Red(),
+                rank: 0,
+            },
+            clued: true,
+            play: true,
+            trash: false,
+            quantum,
+            locked: false,
+            fixed: false,
+            turn: 3,
+            delayed: 0,
+            waited: None,
+            callbacks: false,
+            promised: None,
+        };
+        let mut belief = MisplayBelief {
+            card: red_two,
+            slot,
+            pending: Vec::new(),
+            surely_playable: true,
+            delayed: false,
+        };
+        assert_eq!(belief.cause(), MisplayCause::WrongInference());
+        belief.surely_playable = false;
+        assert_eq!(belief.cause(), MisplayCause::StalePlayFlag());
+        belief.delayed = true;
+        assert_eq!(belief.cause(), MisplayCause::DelayedPlay());
+        belief.pending.push(Callback::WaitingPlay {
+            delayed_slot: 4,
+            pending_slot: 2,
+        });
+        assert_eq!(belief.cause(), MisplayCause::WrongPrompt());
+        belief.slot.promised = Some(2);
+        assert_eq!(belief.cause(), MisplayCause::WrongFinesse());
+        assert!(belief.report().belief.starts_with("r2 was"));
+
+        belief.slot.quantum.add_card(&red_two, false);
+        belief.slot = Slot {
+            quantum: belief.slot.quantum,
+            ..slot
+        };
+        belief.pending.clear();
+        belief.surely_playable = true;
+        belief.delayed = false;
+        assert_eq!(belief.cause(), MisplayCause::Unexplained());
+    }
+}
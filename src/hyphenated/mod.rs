@@ -1,5 +1,6 @@
 mod card_states;
 mod line;
+mod misplay;
 mod slot;
 
 use crate::card_quantum::Variant;
@@ -7,8 +8,10 @@ use crate::game;
 
 use slog;
 
+pub use line::Callback;
 pub use line::Line;
 pub use line::LineScore;
+pub use misplay::MisplayBelief;
 pub use slot::Slot;
 
 impl std::fmt::Debug for HyphenatedPlayer {
@@ -34,6 +37,7 @@ impl std::fmt::Debug for HyphenatedPlayer {
     }
 }
 
+#[derive(Clone)]
 pub struct HyphenatedPlayer {
     debug: bool,
     variant: Variant,
@@ -50,7 +54,7 @@ impl HyphenatedPlayer {
     fn _new(debug: bool, logger: slog::Logger) -> Self {
         Self {
             debug,
-            variant: Variant {},
+            variant: Variant::no_variant(),
             turn: 0,
             line: line::Line::new(4, 0),
             logger,
@@ -67,12 +71,24 @@ impl HyphenatedPlayer {
 }
 
 impl game::PlayerStrategy for HyphenatedPlayer {
-    fn init(&mut self, num_players: u8, own_player: u8) {
-        self.variant = Variant {};
+    fn name(&self) -> String {
+        "hyphenated".to_string()
+    }
+
+    fn init(
+        &mut self,
+        num_players: u8,
+        own_player: u8,
+        variant: Variant,
+        options: game::GameOptions,
+    ) {
+        self.variant = variant;
         self.turn = 0;
         self.line = line::Line::with_logger(
             num_players,
             own_player,
+            variant,
+            options,
             self.logger.new(slog::o!("turn" => "0")),
         );
     }
@@ -100,7 +116,12 @@ impl game::PlayerStrategy for HyphenatedPlayer {
         self.turn += 1;
     }
 
-    fn act(&mut self, status: &game::GameStatus) -> game::Move {
+    fn explain_misplay(&self, pos: usize, card: game::Card) -> Option<game::MisplayReport> {
+        Some(self.line.misplay_belief(pos as u8, card).report())
+    }
+
+    fn act(&mut self, view: &game::PlayerView) -> game::Move {
+        let status = view.status;
         if let Some(play_move) = self.line.play() {
             return play_move;
         }
@@ -117,29 +138,17 @@ impl game::PlayerStrategy for HyphenatedPlayer {
         if self.debug {
             println!("discarding score: {:?}", best_score);
         }
-        for player in 1..self.line.hands.num_players {
-            for suit in self.variant.suits().iter() {
-                let clue = game::Clue::Color(suit.clue_color());
-                if let Some(score) = self.line.clone().clue(player as usize, clue) {
-                    if self.debug {
-                        println!("considered cluing {:?} to {player} with {:?}", clue, score);
-                    }
-                    if score > best_score {
-                        best_move = game::Move::Clue(player, clue);
-                        best_score = score;
-                    }
+        for legal_move in self.line.legal_moves(status.clues) {
+            let game::Move::Clue(player, clue) = legal_move else {
+                continue;
+            };
+            if let Some(score) = self.line.clone().clue(player as usize, clue) {
+                if self.debug {
+                    println!("considered cluing {:?} to {player} with {:?}", clue, score);
                 }
-            }
-            for rank in 1..=5 {
-                let clue = game::Clue::Rank(rank);
-                if let Some(score) = self.line.clone().clue(player as usize, clue) {
-                    if self.debug {
-                        println!("considered cluingg {:?} to {player} with {:?}", clue, score);
-                    }
-                    if score > best_score {
-                        best_move = game::Move::Clue(player, clue);
-                        best_score = score;
-                    }
+                if score > best_score {
+                    best_move = legal_move;
+                    best_score = score;
                 }
             }
         }
@@ -13,11 +13,19 @@ pub struct Slot {
     pub fixed: bool,
     pub turn: i8,
     pub delayed: u8,
+    /// last turn the slot was delayed (waiting for other cards to be played)
+    pub waited: Option<i8>,
     pub callbacks: bool,
     pub promised: Option<i8>,
 }
 
 impl Slot {
+    /// Waits for one more card to be played first
+    pub fn delay(&mut self, turn: i8) {
+        self.delayed += 1;
+        self.waited = Some(turn);
+    }
+
     pub fn update_slot_attributes(&mut self, card_states: &CardStates) {
         if self.delayed > 0 {
             return;
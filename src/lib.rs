@@ -4,6 +4,10 @@ pub mod game;
 mod card_quantum;
 pub use card_quantum::CardQuantum;
 pub use card_quantum::Variant;
+pub mod human;
 pub mod hyphenated;
 mod position_set;
 pub use position_set::PositionSet;
+pub mod solver;
+pub mod stats;
+pub mod strategies;
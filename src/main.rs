@@ -1,34 +1,265 @@
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use rand::prelude::*;
-use std::io::prelude::*;
-use std::{env, io, ops::AddAssign, thread};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    io,
+    ops::{AddAssign, Range},
+    path::{Path, PathBuf},
+    thread,
+};
 
 use raikan::*;
 
+/// Hanabi bot following the H-Group conventions
+///
+/// Without a command, four hyphenated bots play a game printing their reasoning
+/// (the same as `play --debug --strategy hyphenated:debug`).
+#[derive(Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// Directory to write hanab.live JSON replays of all games to
+    #[arg(long, global = true)]
+    replays: Option<PathBuf>,
+    /// Seed of the debug game (random by default)
+    seed: Option<u64>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play a single game in the terminal (by default as first player)
+    Play {
+        /// Seed of the deck (random by default)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Print the internal state of the game after every turn
+        #[arg(long)]
+        debug: bool,
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Simulate many games and print statistics
+    Stats {
+        /// Range of seeds to play, e.g. `1000..2000`
+        #[arg(long, value_parser = parse_seeds, conflicts_with = "games")]
+        seeds: Option<Range<u64>>,
+        /// Number of games to play, starting with seed 0
+        #[arg(long, default_value_t = 100_000)]
+        games: u64,
+        /// Number of worker threads (all available cores by default)
+        #[arg(long)]
+        threads: Option<usize>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Compare the per-game output of two stats runs seed by seed
+    Compare {
+        old: PathBuf,
+        new: PathBuf,
+        /// Maximal number of games to list per section
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Print the lines of hyphenated players at the diverging action of listed games
+        #[arg(long)]
+        lines: bool,
+    },
+    /// Follow a hanab.live replay (URL or JSON file) with hyphenated players
+    Replay {
+        source: String,
+        /// Stop after this many actions and print the line of the next player
+        #[arg(long)]
+        turn: Option<u8>,
+    },
+}
+
+#[derive(Args)]
+struct TableArgs {
+    /// Number of players
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(2..=6))]
+    players: u8,
+    /// Strategy per seat, e.g. `hyphenated:debug,discard` (comma separated);
+    /// a single strategy is used for all seats.
+    /// Available: hyphenated[:debug], human, discard, play, rand_clue
+    #[arg(long = "strategy", value_delimiter = ',')]
+    strategies: Vec<strategies::StrategySpec>,
+}
+
+impl TableArgs {
+    /// Strategies for every seat, `default` fills seats that were not given explicitly
+    fn lineup(&self, default: impl Fn(u8) -> &'static str) -> Vec<strategies::StrategySpec> {
+        match self.strategies.len() {
+            0 => (0..self.players)
+                .map(|seat| default(seat).parse().expect("default strategies exist"))
+                .collect(),
+            1 => vec![self.strategies[0].clone(); self.players as usize],
+            n if n == self.players as usize => self.strategies.clone(),
+            n => Cli::command()
+                .error(
+                    clap::error::ErrorKind::WrongNumberOfValues,
+                    format!("{n} strategies given for {} players", self.players),
+                )
+                .exit(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// One line per game and a summary
+    Text,
+    /// Only the summary
+    Summary,
+    /// One JSON object per game on stdout and the summary object on stderr
+    /// (instead of the text summary)
+    Json,
+    /// CSV with header for the games on stdout and the summary on stderr
+    /// (instead of the text summary)
+    Csv,
+}
+
+/// Formats a record as CSV row, optionally preceded by the header
+fn csv_line(record: &impl Serialize, header: bool) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(header)
+        .from_writer(Vec::new());
+    writer.serialize(record).expect("records are serializable");
+    String::from_utf8(writer.into_inner().expect("writing to memory succeeds"))
+        .expect("CSV of strings and numbers is UTF-8")
+}
+
+fn parse_seeds(range: &str) -> Result<Range<u64>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| "expected a range like 0..1000".to_string())?;
+    let start: u64 = start.parse().map_err(|error| format!("{error}"))?;
+    let end: u64 = end.parse().map_err(|error| format!("{error}"))?;
+    if start >= end {
+        return Err("the range of seeds is empty".to_string());
+    }
+    Ok(start..end)
+}
+
 fn main() -> io::Result<()> {
-    let args: Vec<String> = env::args().collect();
+    let cli = Cli::parse();
+    if let Some(dir) = &cli.replays {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    match cli.command {
+        None => {
+            let lineup = vec!["hyphenated:debug".parse().expect("registered strategy"); 4];
+            play(&lineup, cli.seed, true, cli.replays)
+        }
+        Some(Command::Play { seed, debug, table }) => {
+            let lineup = table.lineup(|seat| if seat == 0 { "human" } else { "hyphenated" });
+            play(&lineup, seed, debug, cli.replays)
+        }
+        Some(Command::Stats {
+            seeds,
+            games,
+            threads,
+            format,
+            table,
+        }) => {
+            let lineup = table.lineup(|_| "hyphenated");
+            if let Some(spec) = lineup.iter().find(|spec| spec.is_interactive()) {
+                Cli::command()
+                    .error(
+                        clap::error::ErrorKind::InvalidValue,
+                        format!(
+                            "the {} strategy is interactive and can't simulate games",
+                            spec.name()
+                        ),
+                    )
+                    .exit();
+            }
+            let threads = threads
+                .unwrap_or_else(|| thread::available_parallelism().map_or(1, |num| num.get()));
+            run_stats(
+                &lineup,
+                seeds.unwrap_or(0..games),
+                threads,
+                format,
+                cli.replays,
+            );
+            Ok(())
+        }
+        Some(Command::Compare {
+            old,
+            new,
+            limit,
+            lines,
+        }) => compare(&old, &new, limit, lines),
+        Some(Command::Replay { source, turn }) => replay(&source, turn),
+    }
+}
+
+fn play(
+    lineup: &[strategies::StrategySpec],
+    seed: Option<u64>,
+    debug: bool,
+    replay_dir: Option<PathBuf>,
+) -> io::Result<()> {
+    let mut strategies = strategies::build_lineup(lineup);
+    let mut players = game::seats(&mut strategies);
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().r#gen());
+    let mut game = game::Game::new(&mut players, debug, seed);
+    let score = game.run(&mut players);
+    println!("{:?} with score {score} (seed {seed})", game.state);
+    if let Some(url) = game.replay_url() {
+        println!("{url}");
+    }
+    if let Some(dir) = replay_dir {
+        game.write_replay(dir.join(format!("{seed}.json")))?;
+    }
+    Ok(())
+}
 
-    if args.contains(&"stats".to_string()) {
-        run_stats();
-    } else if args.contains(&"debug_reg".to_string()) {
-        debug_regressions(&args[2], &args[3])?;
+fn replay(source: &str, turn: Option<u8>) -> io::Result<()> {
+    let json = if Path::new(source).is_file() {
+        let mut replay: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(source)?)?;
+        if let Some(turn) = turn
+            && let Some(actions) = replay["actions"].as_array_mut()
+        {
+            actions.truncate(turn as usize);
+        }
+        Some(replay)
     } else {
-        let mut alice = hyphenated::HyphenatedPlayer::new(true);
-        let mut bob = hyphenated::HyphenatedPlayer::new(true);
-        let mut carl = hyphenated::HyphenatedPlayer::new(true);
-        let mut daniel = hyphenated::HyphenatedPlayer::new(true);
-        let mut players: Vec<&mut dyn game::PlayerStrategy> =
-            vec![&mut alice, &mut bob, &mut carl, &mut daniel];
-        println!("args: {:?}", args);
-        let seed = if args.len() > 1 {
-            args[1].parse().expect("Invalid seed format")
-        } else {
-            let mut seed_rng = rand::thread_rng();
-            seed_rng.r#gen()
-        };
-        let mut game = game::Game::new(&mut players, true, seed);
-        game.run(&mut players);
-        println!("Used seed {}", seed);
-        game.print_replay();
+        None
+    };
+    let invalid_replay = |error: game::ReplayError| -> ! {
+        Cli::command()
+            .error(clap::error::ErrorKind::InvalidValue, error)
+            .exit()
+    };
+    let num_players = match &json {
+        Some(replay) => replay["players"].as_array().map_or(0, Vec::len),
+        None => {
+            game::Game::replay_url_num_players(source).unwrap_or_else(|error| invalid_replay(error))
+        }
+    };
+    let mut bots: Vec<hyphenated::HyphenatedPlayer> = (0..num_players)
+        .map(|_| hyphenated::HyphenatedPlayer::new(false))
+        .collect();
+    let mut players = game::seats(&mut bots);
+    let game = match (json, turn) {
+        (Some(replay), _) => game::Game::from_hanabi_live_json(&replay.to_string(), &mut players),
+        (None, None) => game::Game::from_replay_url(source, &mut players),
+        (None, Some(turn)) => game::Game::from_replay_url_at(turn, source, &mut players),
+    }
+    .unwrap_or_else(|error| invalid_replay(error));
+    println!(
+        "{:?} at turn {} with score {}/{}",
+        game.state, game.status.turn, game.status.score, game.status.max_score
+    );
+    if turn.is_some() && !game.is_over() {
+        let next = game.active_player();
+        println!("Line of player {}: {:?}", next + 1, bots[next].line());
     }
     Ok(())
 }
@@ -44,11 +275,24 @@ struct Stats {
     finished_scores: usize,
     finished_score_intergrals: usize,
     finished_max_scores: usize,
-    finished_dist: [usize; 26],
-    finished_max_dist: [usize; 26],
+    /// completed games by score, up to the maximal score of their variants
+    finished_dist: Vec<usize>,
+    finished_max_dist: Vec<usize>,
     won_games: usize,
     blind_plays: usize,
     strikes: usize,
+    /// sum of the perfect information upper bounds
+    bound_scores: usize,
+    /// games whose deck can't reach the maximal score
+    unwinnable_games: usize,
+    /// games whose best score is known exactly
+    proven_bounds: usize,
+    /// games scoring the upper bound
+    reached_bounds: usize,
+    /// misplays by the cause their strategies gave
+    misplay_causes: BTreeMap<game::MisplayCause, usize>,
+    /// misplays of strategies that can't explain them
+    unreported_misplays: usize,
 }
 
 impl Stats {
@@ -64,21 +308,73 @@ impl Stats {
             finished_scores: 0,
             finished_score_intergrals: 0,
             finished_max_scores: 0,
-            finished_dist: [0; 26],
-            finished_max_dist: [0; 26],
+            finished_dist: Vec::new(),
+            finished_max_dist: Vec::new(),
             won_games: 0,
             blind_plays: 0,
             strikes: 0,
+            bound_scores: 0,
+            unwinnable_games: 0,
+            proven_bounds: 0,
+            reached_bounds: 0,
+            misplay_causes: BTreeMap::new(),
+            unreported_misplays: 0,
+        }
+    }
+
+    fn add_misplays(&mut self, misplays: &[game::Misplay]) {
+        for misplay in misplays {
+            match &misplay.report {
+                Some(report) => *self.misplay_causes.entry(report.cause).or_default() += 1,
+                None => self.unreported_misplays += 1,
+            }
+        }
+    }
+
+    fn misplays(&self, cause: game::MisplayCause) -> usize {
+        self.misplay_causes.get(&cause).copied().unwrap_or_default()
+    }
+
+    fn add_bounds(
+        &mut self,
+        record: &stats::GameRecord,
+        bounds: solver::ScoreBounds,
+        max_score: u8,
+    ) {
+        self.bound_scores += bounds.upper as usize;
+        if bounds.upper < max_score {
+            self.unwinnable_games += 1;
+        }
+        if bounds.is_exact() {
+            self.proven_bounds += 1;
+        }
+        if record.bound_gap() == Some(0) {
+            self.reached_bounds += 1;
+        }
+    }
+
+    /// Counts the score of a finished or won game in the distributions
+    fn add_completed(&mut self, game: &game::Game) {
+        let size = game.variant.max_score() as usize + 1;
+        for dist in [&mut self.finished_dist, &mut self.finished_max_dist] {
+            if dist.len() < size {
+                dist.resize(size, 0);
+            }
         }
+        self.finished_dist[game.status.score as usize] += 1;
+        self.finished_max_dist[game.status.max_score as usize] += 1;
     }
 
     fn median(&self) -> (f64, f64) {
+        if self.finished_games + self.won_games == 0 {
+            return (0.0, 0.0);
+        }
         let num_median = (self.finished_games + self.won_games) / 2;
         let mut num_seen = 0;
         let mut max_seen = 0;
         let mut score_median = 0.0;
         let mut max_median = 0.0;
-        for i in 0..=25 {
+        for i in 0..self.finished_dist.len() {
             if num_median > num_seen + self.finished_dist[i] {
                 num_seen += self.finished_dist[i];
             } else if score_median == 0.0 {
@@ -95,6 +391,81 @@ impl Stats {
 
         (score_median, max_median)
     }
+
+    fn summary(&self) -> Summary {
+        let games = self.invalid_games + self.lost_games + self.finished_games + self.won_games;
+        let completed = self.finished_games + self.won_games;
+        let (median_score, median_max_score) = self.median();
+        Summary {
+            games,
+            won: self.won_games,
+            finished: self.finished_games,
+            lost: self.lost_games,
+            invalid: self.invalid_games,
+            mean_score: mean(self.finished_scores, games),
+            mean_finished_score: mean(self.finished_scores, completed),
+            mean_max_score: mean(self.finished_max_scores, completed),
+            median_score,
+            median_max_score,
+            mean_score_integral: mean(self.finished_score_intergrals, self.finished_games),
+            strikes: self.strikes,
+            blind_plays: self.blind_plays,
+            mean_bound: mean(self.bound_scores, games),
+            unwinnable: self.unwinnable_games,
+            proven_bounds: self.proven_bounds,
+            reached_bounds: self.reached_bounds,
+            wrong_finesse_misplays: self.misplays(game::MisplayCause::WrongFinesse()),
+            wrong_prompt_misplays: self.misplays(game::MisplayCause::WrongPrompt()),
+            delayed_play_misplays: self.misplays(game::MisplayCause::DelayedPlay()),
+            stale_play_flag_misplays: self.misplays(game::MisplayCause::StalePlayFlag()),
+            wrong_inference_misplays: self.misplays(game::MisplayCause::WrongInference()),
+            unexplained_misplays: self.misplays(game::MisplayCause::Unexplained()),
+            unreported_misplays: self.unreported_misplays,
+        }
+    }
+}
+
+/// Average for the summary, 0 without any games (like the medians)
+fn mean(sum: usize, count: usize) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    sum as f64 / count as f64
+}
+
+/// Structured form of the summary of a stats run
+#[derive(Serialize)]
+struct Summary {
+    games: usize,
+    won: usize,
+    finished: usize,
+    lost: usize,
+    invalid: usize,
+    /// counting lost and invalid games as 0
+    mean_score: f64,
+    /// of finished and won games
+    mean_finished_score: f64,
+    mean_max_score: f64,
+    median_score: f64,
+    median_max_score: f64,
+    mean_score_integral: f64,
+    strikes: usize,
+    blind_plays: usize,
+    /// of the best score reachable with perfect information (upper bound)
+    mean_bound: f64,
+    unwinnable: usize,
+    proven_bounds: usize,
+    /// games scoring the upper bound
+    reached_bounds: usize,
+    /// misplays by cause (see [`game::MisplayCause`])
+    wrong_finesse_misplays: usize,
+    wrong_prompt_misplays: usize,
+    delayed_play_misplays: usize,
+    stale_play_flag_misplays: usize,
+    wrong_inference_misplays: usize,
+    unexplained_misplays: usize,
+    /// misplays of strategies that can't explain them
+    unreported_misplays: usize,
 }
 
 impl AddAssign for Stats {
@@ -110,102 +481,151 @@ impl AddAssign for Stats {
         self.finished_score_intergrals += other.finished_score_intergrals;
         self.finished_max_scores += other.finished_max_scores;
         self.won_games += other.won_games;
-        for i in 0..=25 {
-            self.finished_dist[i] += other.finished_dist[i];
-            self.finished_max_dist[i] += other.finished_max_dist[i];
+        for (dist, other_dist) in [
+            (&mut self.finished_dist, other.finished_dist),
+            (&mut self.finished_max_dist, other.finished_max_dist),
+        ] {
+            if dist.len() < other_dist.len() {
+                dist.resize(other_dist.len(), 0);
+            }
+            for (count, other_count) in dist.iter_mut().zip(other_dist) {
+                *count += other_count;
+            }
         }
         self.blind_plays += other.blind_plays;
         self.strikes += other.strikes;
+        self.bound_scores += other.bound_scores;
+        self.unwinnable_games += other.unwinnable_games;
+        self.proven_bounds += other.proven_bounds;
+        self.reached_bounds += other.reached_bounds;
+        for (cause, count) in other.misplay_causes {
+            *self.misplay_causes.entry(cause).or_default() += count;
+        }
+        self.unreported_misplays += other.unreported_misplays;
     }
 }
 
-fn run_stats() {
+fn run_stats(
+    lineup: &[strategies::StrategySpec],
+    seeds: Range<u64>,
+    thread_count: usize,
+    format: OutputFormat,
+    replay_dir: Option<PathBuf>,
+) {
     let mut totals = Stats::new();
 
-    let total = 100_000;
+    let total = seeds.end - seeds.start;
 
-    let thread_count = if let Ok(num) = thread::available_parallelism() {
-        num.get()
-    } else {
-        1
-    };
+    if format == OutputFormat::Csv {
+        let header = csv_line(&stats::GameRecord::default(), true);
+        print!("{}", header.lines().next().unwrap_or_default());
+        println!();
+    }
 
     let mut threads = Vec::new();
 
     for t in 0..thread_count {
+        let replay_dir = replay_dir.clone();
+        let lineup = lineup.to_vec();
+        let seeds = seeds.clone();
         let thread = thread::spawn(move || {
             let mut results = Stats::new();
-            let mut alice = hyphenated::HyphenatedPlayer::new(false);
-            let mut bob = hyphenated::HyphenatedPlayer::new(false);
-            let mut carl = hyphenated::HyphenatedPlayer::new(false);
-            let mut daniel = hyphenated::HyphenatedPlayer::new(false);
-            let mut players: Vec<&mut dyn game::PlayerStrategy> =
-                vec![&mut alice, &mut bob, &mut carl, &mut daniel];
-            for i in 0..total {
-                if i % thread_count != t {
+            let mut strategies = strategies::build_lineup(&lineup);
+            for i in seeds {
+                if i % thread_count as u64 != t as u64 {
                     continue;
                 }
-                let mut game = game::Game::new(&mut players, false, i as u64);
-                game.run(&mut players);
-                match game.state {
-                    game::GameState::Lost() => {
+                let game = game::Game::play_seed(&mut strategies, i);
+                let bounds = solver::score_bounds(
+                    &game.variant,
+                    &game.options,
+                    game.num_players(),
+                    &game::Game::shuffled_deck(game.variant, i),
+                );
+                let record = stats::GameRecord::new(i, &game).with_bounds(bounds);
+                results.add_bounds(&record, bounds, game.variant.max_score());
+                results.add_misplays(&game.misplays);
+                if let Some(dir) = &replay_dir {
+                    let path = dir.join(format!("{i}.json"));
+                    if let Err(error) = game.write_replay(&path) {
+                        eprintln!("could not write replay {}: {error}", path.display());
+                    }
+                }
+                let bound = format!(" {}..{}", bounds.lower, bounds.upper);
+                // games without a shareable replay (e.g. custom positions) omit the URL
+                let url = game
+                    .replay_url()
+                    .map(|url| format!(" {url}"))
+                    .unwrap_or_default();
+                let line = match game.state {
+                    // resigned games score nothing, like lost ones
+                    game::GameState::Lost() | game::GameState::Terminated(_) => {
                         results.lost_games += 1;
                         results.lost_scores += game.status.score as usize;
                         results.lost_max_scores += game.status.max_score as usize;
                         results.strikes += game.status.num_strikes as usize;
                         results.blind_plays += game.status.blind_plays as usize;
-                        println!(
-                            "{i} Lost 0 0 {} {} {}",
+                        format!(
+                            "{i} {} 0 0 {} {}{bound}{url}",
+                            game.state.name(),
                             game.status.turn,
                             game.status.blind_plays,
-                            game.replay_url()
-                        );
+                        )
                     }
                     game::GameState::Finished() => {
                         results.finished_games += 1;
                         results.finished_scores += game.status.score as usize;
-                        results.finished_dist[game.status.score as usize] += 1;
+                        results.add_completed(&game);
                         results.finished_score_intergrals += game.score_integral as usize;
                         results.finished_max_scores += game.status.max_score as usize;
-                        results.finished_max_dist[game.status.max_score as usize] += 1;
                         results.strikes += game.status.num_strikes as usize;
                         results.blind_plays += game.status.blind_plays as usize;
-                        println!(
-                            "{i} Finished {} {} {} {} {}",
+                        format!(
+                            "{i} Finished {} {} {} {}{bound}{url}",
                             game.status.score,
                             game.status.max_score,
                             game.status.turn,
                             game.status.blind_plays,
-                            game.replay_url()
-                        );
+                        )
                     }
                     game::GameState::Won() => {
                         results.won_games += 1;
                         results.finished_scores += game.status.score as usize;
                         results.finished_max_scores += game.status.max_score as usize;
-                        results.finished_dist[game.status.score as usize] += 1;
-                        results.finished_max_dist[game.status.max_score as usize] += 1;
+                        results.add_completed(&game);
                         results.strikes += game.status.num_strikes as usize;
                         results.blind_plays += game.status.blind_plays as usize;
-                        println!(
-                            "{i} Won 25 25 {} {} {}",
+                        format!(
+                            "{i} Won {} {} {} {}{bound}{url}",
+                            game.status.score,
+                            game.status.max_score,
                             game.status.turn,
                             game.status.blind_plays,
-                            game.replay_url()
-                        );
+                        )
                     }
                     game::GameState::Invalid() => {
                         results.invalid_games += 1;
                         results.invalid_scores += game.status.score as usize;
                         results.invalid_max_scores += game.status.max_score as usize;
-                        println!(
-                            "{i} Invalid 0 0 {} {} {}",
-                            game.status.turn,
-                            game.status.blind_plays,
-                            game.replay_url()
-                        );
+                        format!(
+                            "{i} Invalid 0 0 {} {}{bound}{url}",
+                            game.status.turn, game.status.blind_plays,
+                        )
+                    }
+                    game::GameState::Early()
+                    | game::GameState::Mid()
+                    | game::GameState::Final(_) => {
+                        unreachable!("games are played until they are over")
                     }
-                    _ => unimplemented!("Should not happen as final game score"),
+                };
+                match format {
+                    OutputFormat::Text => println!("{line}"),
+                    OutputFormat::Summary => {}
+                    OutputFormat::Json => println!(
+                        "{}",
+                        serde_json::to_string(&record).expect("records are serializable")
+                    ),
+                    OutputFormat::Csv => print!("{}", csv_line(&record, false)),
                 }
             }
             results
@@ -217,150 +637,218 @@ fn run_stats() {
         totals += result;
     }
 
+    // machine readable formats replace the text summary with the summary record,
+    // on stderr to keep stdout a plain list of games
+    match format {
+        OutputFormat::Json => {
+            eprintln!(
+                "{}",
+                serde_json::to_string(&totals.summary()).expect("summary is serializable")
+            );
+            return;
+        }
+        OutputFormat::Csv => {
+            eprint!("{}", csv_line(&totals.summary(), true));
+            return;
+        }
+        OutputFormat::Text | OutputFormat::Summary => {}
+    }
+
     eprintln!("\r{}/{} games simulated", total, total);
+    let summary = totals.summary();
 
     eprintln!(
         "Invalid {:.2}% ({}) games with ~{:.2}/{:.2} scores",
-        (totals.invalid_games * 100) as f64 / total as f64,
+        mean(totals.invalid_games * 100, summary.games),
         totals.invalid_games,
-        totals.invalid_scores as f64 / totals.invalid_games as f64,
-        totals.invalid_max_scores as f64 / totals.invalid_games as f64,
+        mean(totals.invalid_scores, totals.invalid_games),
+        mean(totals.invalid_max_scores, totals.invalid_games),
     );
 
     eprintln!(
         "Lost {:.2}% ({}) games with ~{:.2}/{:.2} scores",
-        (totals.lost_games * 100) as f64 / total as f64,
+        mean(totals.lost_games * 100, summary.games),
         totals.lost_games,
-        totals.lost_scores as f64 / totals.lost_games as f64,
-        totals.lost_max_scores as f64 / totals.lost_games as f64,
+        mean(totals.lost_scores, totals.lost_games),
+        mean(totals.lost_max_scores, totals.lost_games),
     );
 
-    let median = totals.median();
     eprintln!(
         "Finished {} games with ~{:.2}/{:.2} scores (~{:.2} integral) => \n dist: {:?}\n  max: {:?}\n => {:.2} / {:.2}",
         totals.finished_games,
-        totals.finished_scores as f64 / (totals.finished_games + totals.won_games) as f64,
-        totals.finished_max_scores as f64 / (totals.finished_games + totals.won_games) as f64,
-        totals.finished_score_intergrals as f64 / totals.finished_games as f64,
+        summary.mean_finished_score,
+        summary.mean_max_score,
+        summary.mean_score_integral,
         totals.finished_dist,
         totals.finished_max_dist,
-        median.0,
-        median.1,
+        summary.median_score,
+        summary.median_max_score,
     );
     eprintln!(
         "Won {:.2}% {} games",
-        (totals.won_games * 100) as f64 / total as f64,
+        mean(totals.won_games * 100, summary.games),
         totals.won_games
     );
     eprintln!(
         "Overall {} games with ~{:.2} score ({} blind_plays, {} strikes)",
-        totals.invalid_games + totals.lost_games + totals.finished_games + totals.won_games,
-        totals.finished_scores as f64
-            / (totals.invalid_games + totals.lost_games + totals.finished_games + totals.won_games)
-                as f64,
-        totals.blind_plays,
-        totals.strikes
+        summary.games, summary.mean_score, totals.blind_plays, totals.strikes
+    );
+    eprintln!(
+        "Perfect information allows ~{:.2} score ({} unwinnable decks, {} bounds proven), reached in {} games",
+        summary.mean_bound, totals.unwinnable_games, totals.proven_bounds, totals.reached_bounds
+    );
+    let causes: Vec<String> = game::MisplayCause::ALL
+        .iter()
+        .map(|cause| format!("{} {}", totals.misplays(*cause), cause.name()))
+        .collect();
+    eprintln!(
+        "Misplays by cause: {} ({} without explanation)",
+        causes.join(", "),
+        totals.unreported_misplays
     );
 }
 
-fn debug_regressions(old: &str, new: &str) -> io::Result<()> {
-    println!("old: {old}");
-    println!("new: {new}");
-    let old_file = std::fs::File::open(old)?;
-    let new_file = std::fs::File::open(new)?;
-    let old_reader = io::BufReader::new(old_file);
-    let new_reader = io::BufReader::new(new_file);
-
-    for (old_line, new_line) in std::iter::zip(old_reader.lines(), new_reader.lines()) {
-        let old_line = old_line?;
-        let new_line = new_line?;
-        if old_line == new_line {
-            continue;
+fn compare(old: &Path, new: &Path, limit: usize, lines: bool) -> io::Result<()> {
+    let old_records = stats::read_records(io::BufReader::new(std::fs::File::open(old)?))?;
+    let new_records = stats::read_records(io::BufReader::new(std::fs::File::open(new)?))?;
+    let comparison = stats::Comparison::new(&old_records, &new_records);
+
+    println!(
+        "Compared {} games ({} seeds only in one run)",
+        comparison.games, comparison.unmatched
+    );
+    if let (Some(old_mean), Some(new_mean)) = (comparison.old_mean(), comparison.new_mean()) {
+        println!(
+            "Mean score {old_mean:.3} -> {new_mean:.3} ({:+.3})",
+            new_mean - old_mean
+        );
+    }
+    let num_regressions = comparison.regressions().count();
+    let num_improvements = comparison.improvements().count();
+    println!(
+        "{num_regressions} regressions, {num_improvements} improvements, {} unchanged",
+        comparison.games - num_regressions - num_improvements
+    );
+
+    match comparison.significance() {
+        Some(significance) => {
+            println!("\nPaired over {} seeds (95% confidence):", comparison.games);
+            println!(
+                "  score {:+.3} ({:+.3} .. {:+.3}), {}",
+                significance.mean_delta,
+                significance.mean_delta_interval.0,
+                significance.mean_delta_interval.1,
+                if significance.is_significant() {
+                    "significant"
+                } else {
+                    "not significant"
+                }
+            );
+            println!(
+                "  win rate {:.2}% -> {:.2}% ({:+.2}% .. {:+.2}%)",
+                significance.old_win_rate * 100.0,
+                significance.new_win_rate * 100.0,
+                significance.win_rate_delta_interval.0 * 100.0,
+                significance.win_rate_delta_interval.1 * 100.0
+            );
+            println!(
+                "  sign test: {} better, {} worse, p = {:.2e}",
+                significance.improved, significance.regressed, significance.sign_test_p
+            );
         }
-        let old_parts: Vec<&str> = old_line.split(' ').collect();
-        let new_parts: Vec<&str> = new_line.split(' ').collect();
-        if new_parts[1] != "Finished" {
+        None => println!(
+            "\nInsufficient data: paired statistics need at least 2 shared seeds, got {}",
+            comparison.games
+        ),
+    }
+
+    let transitions = comparison.transitions();
+    if !transitions.is_empty() {
+        println!("\nState transitions:");
+        for ((from, to), count) in transitions {
+            println!("  {from} -> {to}: {count}");
+        }
+    }
+
+    for (title, changes) in [
+        ("Regressions", comparison.regressions().collect::<Vec<_>>()),
+        ("Improvements", comparison.improvements().rev().collect()),
+    ] {
+        if changes.is_empty() {
             continue;
         }
-        if old_parts[2].parse::<u8>().unwrap() > new_parts[2].parse::<u8>().unwrap() {
+        println!("\n{title} by diverging actions:");
+        for group in stats::Comparison::by_divergence(changes.iter().copied()).into_values() {
+            let seeds: Vec<String> = group
+                .iter()
+                .take(limit)
+                .map(|change| change.old.seed.to_string())
+                .collect();
+            let name = match group[0].divergence {
+                Some(divergence) => divergence.actions(),
+                _ => "unknown (no comparable replays)".to_string(),
+            };
+            let more = if group.len() > limit { ", ..." } else { "" };
             println!(
-                "{} {} ({}/{}) vs {} ({}/{}) => {}",
-                old_parts[0],
-                old_parts[1],
-                old_parts[2],
-                old_parts[3],
-                new_parts[1],
-                new_parts[2],
-                new_parts[3],
-                new_parts.last().expect("expected format"),
+                "  {name}: {} (seeds {}{more})",
+                group.len(),
+                seeds.join(", ")
             );
-            let mut old_replay = old_parts.last().expect("expected format")[31..].split(',');
-            let mut new_replay = new_parts.last().expect("expected format")[31..].split(',');
-            let old_deck = old_replay.next().unwrap();
-            let new_deck = new_replay.next().unwrap();
-            let old_actions = old_replay.next().unwrap();
-            let new_actions = new_replay.next().unwrap();
-            let old_options = old_replay.next().unwrap();
-            let new_options = new_replay.next().unwrap();
-            assert_eq!(old_deck, new_deck);
-            assert_ne!(old_actions, new_actions);
-            let num_players = new_deck
-                .chars()
-                .next()
-                .expect("deck should be non-empty")
-                .to_digit(10)
-                .expect("player count must be a number") as u8;
-            assert_eq!(num_players, 4);
-            let mut old_h1 = hyphenated::HyphenatedPlayer::new(false);
-            let mut old_h2 = hyphenated::HyphenatedPlayer::new(false);
-            let mut old_h3 = hyphenated::HyphenatedPlayer::new(false);
-            let mut old_h4 = hyphenated::HyphenatedPlayer::new(false);
-            let mut old_players: Vec<&mut dyn game::PlayerStrategy> =
-                vec![&mut old_h1, &mut old_h2, &mut old_h3, &mut old_h4];
-            let mut new_h1 = hyphenated::HyphenatedPlayer::new(false);
-            let mut new_h2 = hyphenated::HyphenatedPlayer::new(false);
-            let mut new_h3 = hyphenated::HyphenatedPlayer::new(false);
-            let mut new_h4 = hyphenated::HyphenatedPlayer::new(false);
-            let mut new_players: Vec<&mut dyn game::PlayerStrategy> =
-                vec![&mut new_h1, &mut new_h2, &mut new_h3, &mut new_h4];
-
-            let mut unchanged = 0;
-            for (old_action, new_action) in std::iter::zip(old_actions.chars(), new_actions.chars())
-            {
-                if old_action == new_action {
-                    unchanged += 1;
-                } else {
-                    break;
-                }
+        }
+        println!("\n{title}:");
+        for change in changes.iter().take(limit) {
+            print_change(change);
+            if lines && let Some(divergence) = change.divergence {
+                print_divergence_lines(change, divergence)?;
             }
-            println!("unchanged: {unchanged}");
-
-            let turn = unchanged / 2;
+        }
+    }
+    Ok(())
+}
 
-            game::Game::from_replay(turn, old_deck, old_actions, old_options, &mut old_players);
-            let target_player = (turn - 1) % num_players;
-            println!("target player: {target_player}");
+fn print_change(change: &stats::Change) {
+    let divergence = change.divergence.map_or(String::new(), |divergence| {
+        format!(
+            " diverged at turn {} (player {}: {})",
+            divergence.turn,
+            divergence.player + 1,
+            divergence.actions()
+        )
+    });
+    println!(
+        "  {} {} {}/{} -> {} {}/{} ({:+}){divergence} {}",
+        change.old.seed,
+        change.old.state,
+        change.old.effective_score(),
+        change.old.max_score,
+        change.new.state,
+        change.new.effective_score(),
+        change.new.max_score,
+        change.delta(),
+        change.new.replay_url.as_deref().unwrap_or_default(),
+    );
+}
 
-            println!("Old replay: {}", old_parts[5]);
-            let old_line = match target_player {
-                0 => old_h1.line(),
-                1 => old_h2.line(),
-                2 => old_h3.line(),
-                _ => old_h4.line(),
-            };
-            println!("Old line {:?}", old_line);
-
-            println!("New replay: {}", new_parts[5]);
-            game::Game::from_replay(turn, new_deck, new_actions, new_options, &mut new_players);
-            let new_line = match target_player {
-                0 => new_h1.line(),
-                1 => new_h2.line(),
-                2 => new_h3.line(),
-                _ => new_h4.line(),
-            };
-            println!("new line {:?}", new_line);
-            break;
-        }
+/// Replays both games with hyphenated players up to the diverging action and
+/// prints the line of the diverging player
+fn print_divergence_lines(change: &stats::Change, divergence: stats::Divergence) -> io::Result<()> {
+    for (name, record) in [("Old", &change.old), ("New", &change.new)] {
+        let url = record
+            .replay_url
+            .as_deref()
+            .ok_or_else(|| io::Error::other("divergences are only found between replays"))?;
+        let mut bots: Vec<hyphenated::HyphenatedPlayer> = (0..divergence.num_players)
+            .map(|_| hyphenated::HyphenatedPlayer::new(false))
+            .collect();
+        let mut players = game::seats(&mut bots);
+        // the identical actions before, so the diverging player is about to act
+        let turn = u8::try_from(divergence.turn).map_err(io::Error::other)?;
+        game::Game::from_replay_url_at(turn, url, &mut players).map_err(io::Error::other)?;
+        println!(
+            "{name} line of player {}: {:?}",
+            divergence.player + 1,
+            bots[divergence.player as usize].line()
+        );
     }
     Ok(())
 }
@@ -0,0 +1,354 @@
+use crate::card_quantum::Variant;
+use crate::game::{Card, GameOptions, PlayOrder};
+
+/// Range of the best score players seeing all cards can reach with a deck
+///
+/// The upper bound follows from the deck order alone: every card has to be
+/// drawn before it is played and only the moves that draw a card plus the
+/// final round can play cards at all. The lower bound is the score of a greedy
+/// playout that looks at all cards. If both match, the best score is proven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreBounds {
+    pub lower: u8,
+    pub upper: u8,
+}
+
+impl ScoreBounds {
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+}
+
+/// Bounds the best score for a deck given in drawing order (as dealt by [`crate::game::Game`])
+pub fn score_bounds(
+    variant: &Variant,
+    options: &GameOptions,
+    num_players: u8,
+    deck: &[Card],
+) -> ScoreBounds {
+    let upper = upper_bound(variant, options, num_players, deck);
+    let lower = greedy_score(variant, options, num_players, deck);
+    debug_assert!(lower <= upper, "greedy playout beats the upper bound");
+    ScoreBounds {
+        lower: lower.min(upper),
+        upper,
+    }
+}
+
+/// Score no sequence of moves can exceed
+///
+/// Clue tokens, strikes and hand sizes are ignored: only the drawing order
+/// and the number of moves that play or discard a card limit the score.
+/// Every play takes one of those moves and a card can only be played after
+/// it and all cards below it on its stack were drawn. The stacks are
+/// lowered, latest available card first, until such a schedule exists.
+pub fn upper_bound(variant: &Variant, options: &GameOptions, num_players: u8, deck: &[Card]) -> u8 {
+    let dealt = (num_players * options.hand_size(num_players)) as usize;
+    // one move per drawn card plus the final round
+    let moves = deck.len().saturating_sub(dealt) + num_players as usize;
+    // first move (counting only plays and discards) that can play the card
+    let available = |card: &Card| {
+        deck.iter()
+            .position(|current| current == card)
+            .map(|index| index.saturating_sub(dealt) + if index < dealt { 1 } else { 2 })
+    };
+
+    let chains: Vec<Vec<usize>> = variant
+        .suits()
+        .iter()
+        .map(|suit| {
+            let order = variant.play_order(suit);
+            let mut releases: Vec<usize> = match order {
+                // any order needs all cards: the n-th card comes after n cards are available
+                PlayOrder::Open() => {
+                    let mut releases: Vec<usize> = (1..=5)
+                        .filter_map(|rank| available(&Card { suit: *suit, rank }))
+                        .collect();
+                    releases.sort_unstable();
+                    releases
+                }
+                _ => (0..5)
+                    .map_while(|step| order.rank(step))
+                    .map_while(|rank| available(&Card { suit: *suit, rank }))
+                    .collect(),
+            };
+            for step in 1..releases.len() {
+                releases[step] = releases[step].max(releases[step - 1]);
+            }
+            releases
+        })
+        .collect();
+
+    let mut heights: Vec<usize> = chains.iter().map(Vec::len).collect();
+    loop {
+        let mut releases: Vec<usize> = chains
+            .iter()
+            .zip(&heights)
+            .flat_map(|(chain, &height)| chain[..height].iter().copied())
+            .collect();
+        releases.sort_unstable_by(|a, b| b.cmp(a));
+        // the n latest cards need n moves from the n-th latest release on
+        if releases
+            .iter()
+            .enumerate()
+            .all(|(count, &release)| count + release <= moves)
+        {
+            break;
+        }
+        // dropping the latest top card relieves every violated window
+        let suit = (0..chains.len())
+            .filter(|&suit| heights[suit] > 0)
+            .max_by_key(|&suit| chains[suit][heights[suit] - 1])
+            .expect("only cards make a schedule infeasible");
+        heights[suit] -= 1;
+    }
+    heights.iter().sum::<usize>() as u8
+}
+
+/// Score of a simple playout by players seeing all cards (including their own)
+///
+/// Players play whenever they can, discard useless cards, stall with clues while
+/// someone else can play and otherwise discard the card needed latest (keeping
+/// the last copies of cards as long as possible). It never misplays.
+pub fn greedy_score(
+    variant: &Variant,
+    options: &GameOptions,
+    num_players: u8,
+    deck: &[Card],
+) -> u8 {
+    let mut table = Table::new(*variant, options.hand_size(num_players), num_players, deck);
+    table.play_out();
+    table.score
+}
+
+struct Table<'a> {
+    variant: Variant,
+    hands: Vec<Vec<Card>>,
+    deck: std::slice::Iter<'a, Card>,
+    played: Vec<u8>,
+    play_orders: Vec<PlayOrder>,
+    /// copies neither played nor discarded, by suit index and rank
+    remaining: Vec<[u8; 5]>,
+    clues: u8,
+    score: u8,
+}
+
+impl<'a> Table<'a> {
+    fn new(variant: Variant, hand_size: u8, num_players: u8, deck: &'a [Card]) -> Self {
+        let mut remaining = vec![[0; 5]; variant.len()];
+        for card in deck {
+            remaining[variant.suit_index(&card.suit)][card.rank as usize - 1] += 1;
+        }
+        let mut deck = deck.iter();
+        let hands = (0..num_players)
+            .map(|_| {
+                let mut hand: Vec<Card> = deck.by_ref().take(hand_size as usize).copied().collect();
+                hand.reverse();
+                hand
+            })
+            .collect();
+        Self {
+            variant,
+            hands,
+            deck,
+            played: vec![0; variant.len()],
+            play_orders: variant
+                .suits()
+                .iter()
+                .map(|suit| variant.play_order(suit))
+                .collect(),
+            remaining,
+            clues: 8,
+            score: 0,
+        }
+    }
+
+    fn play_out(&mut self) {
+        let num_players = self.hands.len();
+        let mut final_turns = num_players;
+        let mut player = 0;
+        while self.score < self.variant.max_score() {
+            let deck_empty = self.deck.as_slice().is_empty();
+            self.turn(player);
+            if deck_empty {
+                final_turns -= 1;
+                if final_turns == 0 {
+                    break;
+                }
+            }
+            player = (player + 1) % num_players;
+        }
+    }
+
+    fn turn(&mut self, player: usize) {
+        let hand = &self.hands[player];
+        if let Some(pos) = (0..hand.len())
+            .filter(|&pos| self.is_playable(&hand[pos]))
+            // open up further plays first
+            .max_by_key(|&pos| self.is_next_held(&hand[pos]))
+        {
+            let card = self.remove(player, pos);
+            self.play(card);
+            return;
+        }
+        let useless = (0..hand.len()).find(|&pos| self.is_useless(player, pos));
+        let others_can_play =
+            self.hands.iter().enumerate().any(|(other, hand)| {
+                other != player && hand.iter().any(|card| self.is_playable(card))
+            });
+        let stall = self.clues > 0 && (others_can_play || self.deck.as_slice().is_empty());
+        let discard = match useless {
+            Some(pos) if self.clues < 8 => Some(pos),
+            _ if stall || self.clues == 8 => None,
+            _ => (0..hand.len()).min_by_key(|&pos| {
+                let card = &hand[pos];
+                (
+                    self.is_critical(card),
+                    std::cmp::Reverse(self.distance(card)),
+                )
+            }),
+        };
+        match discard {
+            Some(pos) => {
+                self.remove(player, pos);
+                self.clues += 1;
+            }
+            None => self.clues -= 1,
+        }
+    }
+
+    /// Removes a card from the hand and draws a replacement
+    fn remove(&mut self, player: usize, pos: usize) -> Card {
+        let card = self.hands[player].remove(pos);
+        self.remaining[self.variant.suit_index(&card.suit)][card.rank as usize - 1] -= 1;
+        if let Some(&drawn) = self.deck.next() {
+            self.hands[player].insert(0, drawn);
+        }
+        card
+    }
+
+    fn play(&mut self, card: Card) {
+        let suit_index = self.variant.suit_index(&card.suit);
+        self.play_orders[suit_index] = self.play_orders[suit_index].started_with(card.rank);
+        self.played[suit_index] += 1;
+        self.score += 1;
+        if self.played[suit_index] == 5 && self.clues < 8 {
+            self.clues += 1;
+        }
+    }
+
+    fn is_playable(&self, card: &Card) -> bool {
+        self.distance(card) == Some(0)
+    }
+
+    /// Number of cards to be played before the card (`None` if it can't be played anymore)
+    fn distance(&self, card: &Card) -> Option<u8> {
+        let suit_index = self.variant.suit_index(&card.suit);
+        let played = self.played[suit_index];
+        let step = match self.play_orders[suit_index] {
+            PlayOrder::Open() => return Some((card.rank - 1).min(5 - card.rank)),
+            order => order.step(card.rank)?,
+        };
+        let order = self.play_orders[suit_index];
+        let reachable = (played..step).all(|step| {
+            order
+                .rank(step)
+                .is_some_and(|rank| self.remaining[suit_index][rank as usize - 1] > 0)
+        });
+        if step < played || !reachable {
+            return None;
+        }
+        Some(step - played)
+    }
+
+    fn is_critical(&self, card: &Card) -> bool {
+        self.remaining[self.variant.suit_index(&card.suit)][card.rank as usize - 1] == 1
+    }
+
+    /// Whether the card can't be played or another copy is in a hand already
+    fn is_useless(&self, player: usize, pos: usize) -> bool {
+        let card = &self.hands[player][pos];
+        self.distance(card).is_none()
+            || self.hands.iter().enumerate().any(|(other, hand)| {
+                hand.iter().enumerate().any(|(other_pos, other_card)| {
+                    other_card == card && (other, other_pos) != (player, pos)
+                })
+            })
+    }
+
+    /// Whether someone holds the card played after this one
+    fn is_next_held(&self, card: &Card) -> bool {
+        self.hands
+            .iter()
+            .flatten()
+            .any(|other| other.suit == card.suit && self.distance(other) == Some(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, GameState};
+    use crate::hyphenated::HyphenatedPlayer;
+
+    fn sorted_deck(variant: &Variant) -> Vec<Card> {
+        let mut deck = Vec::new();
+        for rank in 1..=5 {
+            for suit in variant.suits() {
+                let card = Card { suit: *suit, rank };
+                for _ in 0..variant.card_count(&card) {
+                    deck.push(card);
+                }
+            }
+        }
+        deck
+    }
+
+    #[test]
+    fn bound_sorted_and_buried_decks() {
+        let variant = Variant::default();
+        let options = GameOptions::default();
+        let deck = sorted_deck(&variant);
+        let bounds = score_bounds(&variant, &options, 2, &deck);
+        assert_eq!(
+            bounds,
+            ScoreBounds {
+                lower: 25,
+                upper: 25
+            }
+        );
+        assert!(bounds.is_exact());
+
+        // the last three cards are all red ones: once the first is drawn only
+        // four moves are left for red 1 to 5
+        let red_one = deck[0];
+        let mut buried: Vec<Card> = deck.into_iter().filter(|card| *card != red_one).collect();
+        buried.extend([red_one; 3]);
+        let bounds = score_bounds(&variant, &options, 2, &buried);
+        assert_eq!(bounds.upper, 24);
+        assert!(bounds.lower <= 24);
+
+        // without a green 4 nothing above green 3 counts
+        let missing: Vec<Card> = sorted_deck(&variant)
+            .into_iter()
+            .filter(|card| card.suit != variant.suits()[2] || card.rank != 4)
+            .collect();
+        assert_eq!(upper_bound(&variant, &options, 3, &missing), 23);
+    }
+
+    #[test]
+    fn bots_stay_below_the_bound() {
+        let variant = Variant::default();
+        let options = GameOptions::default();
+        for seed in 0..20 {
+            let mut players: Vec<HyphenatedPlayer> =
+                (0..4).map(|_| HyphenatedPlayer::new(false)).collect();
+            let game = Game::play_seed(&mut players, seed);
+            assert_ne!(game.state, GameState::Invalid());
+            let deck = Game::shuffled_deck(variant, seed);
+            let bounds = score_bounds(&variant, &options, 4, &deck);
+            assert!(bounds.lower <= bounds.upper);
+            assert!(game.status.score <= bounds.upper, "seed {seed}: {bounds:?}");
+        }
+    }
+}
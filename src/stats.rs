@@ -0,0 +1,664 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, HanabiLiveGame};
+use crate::solver::ScoreBounds;
+
+/// Outcome of a single simulated game, as written by `raikan stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub seed: u64,
+    /// final [`crate::game::GameState`], e.g. `Won` or `Lost`
+    pub state: String,
+    pub score: u8,
+    pub max_score: u8,
+    pub turns: u8,
+    pub strikes: u8,
+    pub blind_plays: u8,
+    pub score_integral: u16,
+    /// best score reachable with perfect information is at least this ...
+    #[serde(default)]
+    pub bound_lower: Option<u8>,
+    /// ... and at most this (see [`crate::solver`])
+    #[serde(default)]
+    pub bound_upper: Option<u8>,
+    pub replay_url: Option<String>,
+}
+
+impl GameRecord {
+    pub fn new(seed: u64, game: &Game) -> Self {
+        Self {
+            seed,
+            state: game.state.name().to_string(),
+            score: game.status.score,
+            max_score: game.status.max_score,
+            turns: game.status.turn,
+            strikes: game.status.num_strikes,
+            blind_plays: game.status.blind_plays,
+            score_integral: game.score_integral,
+            bound_lower: None,
+            bound_upper: None,
+            replay_url: game.replay_url(),
+        }
+    }
+
+    pub fn with_bounds(mut self, bounds: ScoreBounds) -> Self {
+        self.bound_lower = Some(bounds.lower);
+        self.bound_upper = Some(bounds.upper);
+        self
+    }
+
+    /// Points missing to the upper bound of the deck (if known)
+    pub fn bound_gap(&self) -> Option<u8> {
+        self.bound_upper
+            .map(|upper| upper.saturating_sub(self.effective_score()))
+    }
+
+    /// Score as counted by hanab.live: lost, invalid and terminated games score nothing
+    pub fn effective_score(&self) -> u8 {
+        match self.state.as_str() {
+            "Lost" | "Invalid" | "Terminated" => 0,
+            _ => self.score,
+        }
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.state == "Won"
+    }
+
+    /// Parses a line of the plain text output:
+    /// `<seed> <state> <score> <max score> <turns> <blind plays> [<lower>..<upper>] [<replay url>]`
+    ///
+    /// Strikes and the score integral are not part of the text and left at 0.
+    pub fn from_text(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 6 || parts.len() > 8 {
+            return None;
+        }
+        let mut optional = parts[6..].iter().peekable();
+        let bounds = match optional.peek().and_then(|part| part.split_once("..")) {
+            Some((lower, upper)) => {
+                optional.next();
+                Some((lower.parse().ok()?, upper.parse().ok()?))
+            }
+            None => None,
+        };
+        let replay_url = optional.next().map(|url| url.to_string());
+        if optional.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            seed: parts[0].parse().ok()?,
+            state: parts[1].to_string(),
+            score: parts[2].parse().ok()?,
+            max_score: parts[3].parse().ok()?,
+            turns: parts[4].parse().ok()?,
+            strikes: 0,
+            blind_plays: parts[5].parse().ok()?,
+            score_integral: 0,
+            bound_lower: bounds.map(|(lower, _upper)| lower),
+            bound_upper: bounds.map(|(_lower, upper)| upper),
+            replay_url,
+        })
+    }
+}
+
+/// Reads the per-game output of `raikan stats` in any of its formats
+/// (JSON Lines, CSV with header or plain text)
+pub fn read_records(mut reader: impl BufRead) -> io::Result<Vec<GameRecord>> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let invalid = |line: usize, error: &dyn std::fmt::Display| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid record in line {line}: {error}"),
+        )
+    };
+    let first_line = content.lines().find(|line| !line.trim().is_empty());
+    if first_line.is_some_and(|line| line.starts_with("seed,")) {
+        return csv::Reader::from_reader(content.as_bytes())
+            .deserialize()
+            .enumerate()
+            .map(|(index, record)| record.map_err(|error| invalid(index + 2, &error)))
+            .collect();
+    }
+    let json = first_line.is_some_and(|line| line.starts_with('{'));
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            if json {
+                serde_json::from_str(line).map_err(|error| invalid(index + 1, &error))
+            } else {
+                GameRecord::from_text(line).ok_or_else(|| invalid(index + 1, &"unexpected format"))
+            }
+        })
+        .collect()
+}
+
+/// Kind of an action in a replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ActionKind {
+    Play(),
+    Discard(),
+    ColorClue(),
+    RankClue(),
+    GameOver(),
+}
+
+impl ActionKind {
+    fn from_hanabi_live(action: u8) -> Option<Self> {
+        match action {
+            0 => Some(Self::Play()),
+            1 => Some(Self::Discard()),
+            2 => Some(Self::ColorClue()),
+            3 => Some(Self::RankClue()),
+            4 => Some(Self::GameOver()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Play() => "play",
+            Self::Discard() => "discard",
+            Self::ColorClue() => "color clue",
+            Self::RankClue() => "rank clue",
+            Self::GameOver() => "game over",
+        })
+    }
+}
+
+/// First action in which two games of the same deck differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divergence {
+    /// number of identical actions before
+    pub turn: usize,
+    /// player making the diverging action
+    pub player: u8,
+    pub num_players: u8,
+    /// diverging actions (`None` if the game ended before)
+    pub old: Option<ActionKind>,
+    pub new: Option<ActionKind>,
+}
+
+impl Divergence {
+    /// Compares two replay URLs; `None` if they can not be decoded, have
+    /// different decks or identical actions
+    pub fn between(old_url: &str, new_url: &str) -> Option<Self> {
+        let old = HanabiLiveGame::from_url(old_url).ok()?;
+        let new = HanabiLiveGame::from_url(new_url).ok()?;
+        // replays may omit cards that were never drawn
+        let same_deck = old.deck.iter().zip(&new.deck).all(|(old, new)| old == new);
+        if !same_deck || old.players.len() != new.players.len() {
+            return None;
+        }
+        let turn = old
+            .actions
+            .iter()
+            .zip(&new.actions)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let kind = |actions: &[crate::game::HanabiLiveAction]| {
+            actions
+                .get(turn)
+                .and_then(|action| ActionKind::from_hanabi_live(action.action))
+        };
+        if turn == old.actions.len() && turn == new.actions.len() {
+            return None;
+        }
+        Some(Self {
+            turn,
+            player: (turn % old.players.len()) as u8,
+            num_players: old.players.len() as u8,
+            old: kind(&old.actions),
+            new: kind(&new.actions),
+        })
+    }
+
+    /// Diverging action kinds, e.g. `rank clue -> play`
+    pub fn actions(&self) -> String {
+        let name = |kind: Option<ActionKind>| kind.map_or("nothing".to_string(), |k| k.to_string());
+        format!("{} -> {}", name(self.old), name(self.new))
+    }
+}
+
+/// Old and new action kind of a [`Divergence`]
+pub type DivergingActions = (Option<ActionKind>, Option<ActionKind>);
+
+/// Outcome of the same seed in two runs
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub old: GameRecord,
+    pub new: GameRecord,
+    pub divergence: Option<Divergence>,
+}
+
+impl Change {
+    pub fn delta(&self) -> i16 {
+        self.new.effective_score() as i16 - self.old.effective_score() as i16
+    }
+}
+
+/// Seed-by-seed comparison of two stats runs (e.g. of two bot versions)
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// number of seeds played in both runs
+    pub games: usize,
+    /// number of seeds only played in one of the runs
+    pub unmatched: usize,
+    pub old_total: u64,
+    pub new_total: u64,
+    pub old_wins: usize,
+    pub new_wins: usize,
+    /// seeds with different scores or end states, worst regressions first
+    pub changes: Vec<Change>,
+}
+
+impl Comparison {
+    pub fn new(old: &[GameRecord], new: &[GameRecord]) -> Self {
+        let new_by_seed: HashMap<u64, &GameRecord> =
+            new.iter().map(|record| (record.seed, record)).collect();
+        let mut comparison = Self {
+            games: 0,
+            unmatched: 0,
+            old_total: 0,
+            new_total: 0,
+            old_wins: 0,
+            new_wins: 0,
+            changes: Vec::new(),
+        };
+        for old_record in old {
+            let Some(new_record) = new_by_seed.get(&old_record.seed) else {
+                continue;
+            };
+            comparison.games += 1;
+            comparison.old_total += old_record.effective_score() as u64;
+            comparison.new_total += new_record.effective_score() as u64;
+            comparison.old_wins += old_record.is_won() as usize;
+            comparison.new_wins += new_record.is_won() as usize;
+            if old_record.effective_score() == new_record.effective_score()
+                && old_record.state == new_record.state
+            {
+                continue;
+            }
+            let divergence = match (&old_record.replay_url, &new_record.replay_url) {
+                (Some(old_url), Some(new_url)) => Divergence::between(old_url, new_url),
+                _ => None,
+            };
+            comparison.changes.push(Change {
+                old: old_record.clone(),
+                new: (*new_record).clone(),
+                divergence,
+            });
+        }
+        // counted per record: runs may contain a seed more than once
+        let old_seeds: HashSet<u64> = old.iter().map(|record| record.seed).collect();
+        comparison.unmatched = old
+            .iter()
+            .filter(|record| !new_by_seed.contains_key(&record.seed))
+            .chain(
+                new.iter()
+                    .filter(|record| !old_seeds.contains(&record.seed)),
+            )
+            .count();
+        comparison
+            .changes
+            .sort_by_key(|change| (change.delta(), change.old.seed));
+        comparison
+    }
+
+    /// Mean score of the old run over the shared seeds (`None` without shared seeds)
+    pub fn old_mean(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.old_total as f64 / self.games as f64)
+    }
+
+    /// Mean score of the new run over the shared seeds (`None` without shared seeds)
+    pub fn new_mean(&self) -> Option<f64> {
+        (self.games > 0).then(|| self.new_total as f64 / self.games as f64)
+    }
+
+    pub fn regressions(&self) -> impl DoubleEndedIterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.delta() < 0)
+    }
+
+    pub fn improvements(&self) -> impl DoubleEndedIterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.delta() > 0)
+    }
+
+    /// Paired statistics over the seeds of both runs
+    ///
+    /// `None` with less than two shared seeds: there is no variance to estimate.
+    pub fn significance(&self) -> Option<Significance> {
+        if self.games < 2 {
+            return None;
+        }
+        let n = self.games as f64;
+        let deltas = self.changes.iter().map(|change| change.delta() as f64);
+        let mean_delta = (self.new_total as f64 - self.old_total as f64) / n;
+        // unchanged seeds contribute a difference of 0
+        let squares: f64 = deltas.map(|delta| delta * delta).sum();
+        let variance = (squares - n * mean_delta * mean_delta) / (n - 1.0);
+        let margin = Z_95 * (variance / n).sqrt();
+
+        let lost_wins = self
+            .changes
+            .iter()
+            .filter(|change| change.old.is_won() && !change.new.is_won())
+            .count() as f64;
+        let new_wins = self
+            .changes
+            .iter()
+            .filter(|change| !change.old.is_won() && change.new.is_won())
+            .count() as f64;
+        let win_rate_delta = (new_wins - lost_wins) / n;
+        let win_variance = (lost_wins + new_wins - n * win_rate_delta * win_rate_delta) / (n - 1.0);
+        let win_margin = Z_95 * (win_variance / n).sqrt();
+
+        let improved = self.improvements().count();
+        let regressed = self.regressions().count();
+        Some(Significance {
+            mean_delta,
+            mean_delta_interval: (mean_delta - margin, mean_delta + margin),
+            old_win_rate: self.old_wins as f64 / n,
+            new_win_rate: self.new_wins as f64 / n,
+            win_rate_delta,
+            win_rate_delta_interval: (win_rate_delta - win_margin, win_rate_delta + win_margin),
+            improved,
+            regressed,
+            sign_test_p: sign_test(improved, regressed),
+        })
+    }
+
+    /// Number of games per change of the end state, e.g. `("Finished", "Lost")`
+    pub fn transitions(&self) -> BTreeMap<(&str, &str), usize> {
+        let mut transitions = BTreeMap::new();
+        for change in &self.changes {
+            if change.old.state != change.new.state {
+                *transitions
+                    .entry((change.old.state.as_str(), change.new.state.as_str()))
+                    .or_default() += 1;
+            }
+        }
+        transitions
+    }
+
+    /// Groups changes by their diverging actions (`None` without replays)
+    pub fn by_divergence<'a>(
+        changes: impl Iterator<Item = &'a Change>,
+    ) -> BTreeMap<Option<DivergingActions>, Vec<&'a Change>> {
+        let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for change in changes {
+            let key = change
+                .divergence
+                .map(|divergence| (divergence.old, divergence.new));
+            groups.entry(key).or_default().push(change);
+        }
+        groups
+    }
+}
+
+/// z value of a two-sided 95% confidence interval
+const Z_95: f64 = 1.959964;
+
+/// Paired comparison of two runs over the same seeds
+///
+/// Intervals are 95% confidence intervals using the normal approximation,
+/// which is fine for the thousands of games of a usual stats run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Significance {
+    /// mean of the per-seed score differences (new - old)
+    pub mean_delta: f64,
+    pub mean_delta_interval: (f64, f64),
+    pub old_win_rate: f64,
+    pub new_win_rate: f64,
+    pub win_rate_delta: f64,
+    pub win_rate_delta_interval: (f64, f64),
+    /// seeds with a higher / lower score in the new run
+    pub improved: usize,
+    pub regressed: usize,
+    /// two-sided p-value of the sign test over improved and regressed seeds
+    pub sign_test_p: f64,
+}
+
+impl Significance {
+    /// Whether the score difference is significant at the 5% level
+    pub fn is_significant(&self) -> bool {
+        self.mean_delta_interval.0 > 0.0 || self.mean_delta_interval.1 < 0.0
+    }
+}
+
+/// Exact two-sided sign test: probability of a split at least as uneven as
+/// `positive` to `negative` if both directions were equally likely
+fn sign_test(positive: usize, negative: usize) -> f64 {
+    let n = positive + negative;
+    if n == 0 {
+        return 1.0;
+    }
+    let k = positive.min(negative);
+    // sum the binomial probabilities P(X = i) for i <= k in log space
+    let ln_half_n = n as f64 * 0.5f64.ln();
+    let mut ln_binomial = 0.0;
+    let mut ln_terms = Vec::with_capacity(k + 1);
+    for i in 0..=k {
+        if i > 0 {
+            ln_binomial += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        ln_terms.push(ln_binomial + ln_half_n);
+    }
+    let max = ln_terms.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let tail = max.exp() * ln_terms.iter().map(|term| (term - max).exp()).sum::<f64>();
+    (2.0 * tail).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(seed: u64, replay_url: Option<&str>) -> GameRecord {
+        GameRecord {
+            seed,
+            state: "Finished".to_string(),
+            score: 23,
+            max_score: 24,
+            turns: 61,
+            strikes: 1,
+            blind_plays: 2,
+            score_integral: 812,
+            bound_lower: None,
+            bound_upper: None,
+            replay_url: replay_url.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn read_all_formats() {
+        let records = vec![
+            record(3, Some("https://hanab.live/replay-json/415abc,05pc,0")).with_bounds(
+                ScoreBounds {
+                    lower: 24,
+                    upper: 25,
+                },
+            ),
+            record(4, None),
+        ];
+        assert_eq!(records[0].bound_gap(), Some(2));
+        assert_eq!(records[1].bound_gap(), None);
+
+        let json: String = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect();
+        assert_eq!(read_records(json.as_bytes()).unwrap(), records);
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for record in &records {
+            writer.serialize(record).unwrap();
+        }
+        let csv = writer.into_inner().unwrap();
+        assert_eq!(read_records(csv.as_slice()).unwrap(), records);
+
+        // written before the bounds were added
+        let csv = "seed,state,score,max_score,turns,strikes,blind_plays,score_integral,replay_url\n\
+                   4,Finished,23,24,61,1,2,812,\n";
+        assert_eq!(read_records(csv.as_bytes()).unwrap(), records[1..]);
+
+        let text = "3 Finished 23 24 61 2 https://hanab.live/replay-json/415abc,05pc,0\n";
+        let parsed = read_records(text.as_bytes()).unwrap();
+        assert_eq!(parsed[0].seed, 3);
+        assert_eq!(parsed[0].replay_url, records[0].replay_url);
+        assert_eq!(parsed[0].bound_upper, None);
+
+        let text = "3 Finished 23 24 61 2 24..25 https://hanab.live/replay-json/415abc,05pc,0\n\
+                    4 Lost 0 0 40 1 25..25\n";
+        let parsed = read_records(text.as_bytes()).unwrap();
+        assert_eq!(
+            (parsed[0].bound_lower, parsed[0].bound_upper),
+            (Some(24), Some(25))
+        );
+        assert_eq!(parsed[0].replay_url, records[0].replay_url);
+        assert_eq!(parsed[1].bound_gap(), Some(25));
+        assert_eq!(parsed[1].replay_url, None);
+
+        let error = read_records("3 Finished\n".as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn compare_runs() {
+        let mut bots: Vec<crate::hyphenated::HyphenatedPlayer> = (0..3)
+            .map(|_| crate::hyphenated::HyphenatedPlayer::new(false))
+            .collect();
+        let old: Vec<GameRecord> = (0..3)
+            .map(|seed| GameRecord::new(seed, &Game::play_seed(&mut bots, seed)))
+            .collect();
+        let mut lineup: Vec<Box<dyn crate::game::PlayerStrategy>> = vec![
+            Box::new(crate::hyphenated::HyphenatedPlayer::new(false)),
+            Box::new(crate::hyphenated::HyphenatedPlayer::new(false)),
+            Box::new(crate::dump_strategies::DiscardPlayer),
+        ];
+        let new = vec![GameRecord::new(1, &Game::play_seed(&mut lineup, 1))];
+
+        let comparison = Comparison::new(&old, &new);
+        assert_eq!(comparison.games, 1);
+        assert_eq!(comparison.unmatched, 2);
+        let duplicated = Comparison::new(&[old[1].clone(), old[1].clone()], &new);
+        assert_eq!((duplicated.games, duplicated.unmatched), (2, 0));
+        assert_eq!(comparison.changes.len(), 1);
+        let change = &comparison.changes[0];
+        assert!(change.delta() < 0);
+        assert_eq!(comparison.regressions().count(), 1);
+        assert_eq!(comparison.improvements().count(), 0);
+        let divergence = change.divergence.expect("same deck, different actions");
+        // the first turn of the discarding player
+        assert_eq!(divergence.turn, 2);
+        assert_eq!(divergence.player, 2);
+        assert_eq!(divergence.new, Some(ActionKind::Discard()));
+
+        assert_eq!(Comparison::new(&old, &old).changes.len(), 0);
+        assert_eq!(
+            Divergence::between(
+                old[1].replay_url.as_ref().unwrap(),
+                old[2].replay_url.as_ref().unwrap()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn lost_games_score_nothing() {
+        let old = vec![record(1, None), record(2, None)];
+        let mut lost = record(1, None);
+        lost.state = "Lost".to_string();
+        let mut won = record(2, None);
+        won.state = "Won".to_string();
+        won.score = 25;
+        let comparison = Comparison::new(&old, &[lost.clone(), won]);
+        assert_eq!(lost.effective_score(), 0);
+        assert_eq!(comparison.old_total, 46);
+        assert_eq!(comparison.new_total, 25);
+        assert_eq!(
+            comparison
+                .changes
+                .iter()
+                .map(Change::delta)
+                .collect::<Vec<_>>(),
+            vec![-23, 2]
+        );
+        assert_eq!(
+            comparison.transitions(),
+            BTreeMap::from([(("Finished", "Lost"), 1), (("Finished", "Won"), 1)])
+        );
+        assert_eq!(
+            Comparison::by_divergence(comparison.changes.iter())
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&None]
+        );
+    }
+
+    #[test]
+    fn sign_test_p_values() {
+        assert_eq!(sign_test(0, 0), 1.0);
+        assert_eq!(sign_test(3, 3), 1.0);
+        assert!((sign_test(0, 5) - 0.0625).abs() < 1e-12);
+        assert!((sign_test(8, 2) - 0.109375).abs() < 1e-12);
+        // far too uneven to be noise, and no underflow for large runs
+        let p = sign_test(5200, 4800);
+        assert!(p > 0.0 && p < 0.001, "{p}");
+    }
+
+    #[test]
+    fn paired_significance() {
+        let old: Vec<GameRecord> = (0..100).map(|seed| record(seed, None)).collect();
+        let mut new = old.clone();
+        for record in new.iter_mut().take(30) {
+            record.score += 1;
+        }
+        new[99].state = "Won".to_string();
+        new[99].score = 25;
+        let significance = Comparison::new(&old, &new)
+            .significance()
+            .expect("enough shared seeds");
+        assert!((significance.mean_delta - 0.32).abs() < 1e-9);
+        assert!(significance.is_significant());
+        assert_eq!(significance.improved, 31);
+        assert_eq!(significance.regressed, 0);
+        assert!(significance.sign_test_p < 1e-8);
+        assert_eq!(significance.old_win_rate, 0.0);
+        assert!((significance.new_win_rate - 0.01).abs() < 1e-12);
+        assert!((significance.win_rate_delta - 0.01).abs() < 1e-12);
+        let (low, high) = significance.win_rate_delta_interval;
+        assert!(low < 0.0 && high > 0.01, "one more win is noise");
+
+        let unchanged = Comparison::new(&old, &old)
+            .significance()
+            .expect("enough shared seeds");
+        assert_eq!(unchanged.mean_delta, 0.0);
+        assert!(!unchanged.is_significant());
+        assert_eq!(unchanged.sign_test_p, 1.0);
+    }
+
+    #[test]
+    fn too_few_shared_seeds() {
+        let old: Vec<GameRecord> = (0..3).map(|seed| record(seed, None)).collect();
+        let new: Vec<GameRecord> = (2..5).map(|seed| record(seed, None)).collect();
+
+        let disjoint = Comparison::new(&old[..2], &new[1..]);
+        assert_eq!(disjoint.games, 0);
+        assert_eq!(disjoint.old_mean(), None);
+        assert_eq!(disjoint.new_mean(), None);
+        assert_eq!(disjoint.significance(), None);
+
+        let single = Comparison::new(&old, &new);
+        assert_eq!(single.games, 1);
+        assert_eq!(single.old_mean(), Some(old[2].score as f64));
+        assert_eq!(single.significance(), None);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::dump_strategies::{DiscardPlayer, PlayPlayer, RandCluePlayer};
+use crate::game::PlayerStrategy;
+use crate::human::HumanPlayer;
+use crate::hyphenated::HyphenatedPlayer;
+
+/// A strategy that can be selected by name
+pub struct StrategyEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// flags that can be appended to the name, e.g. `hyphenated:debug`
+    pub options: &'static [&'static str],
+    /// waits for input in the terminal, so it can't be simulated in bulk
+    pub interactive: bool,
+    build: fn(&[String]) -> Box<dyn PlayerStrategy>,
+}
+
+pub const STRATEGIES: &[StrategyEntry] = &[
+    StrategyEntry {
+        name: "hyphenated",
+        description: "bot following the H-Group conventions",
+        options: &["debug"],
+        interactive: false,
+        build: |options| Box::new(HyphenatedPlayer::new(options.iter().any(|o| o == "debug"))),
+    },
+    StrategyEntry {
+        name: "human",
+        description: "human playing in the terminal",
+        options: &[],
+        interactive: true,
+        build: |_| Box::new(HumanPlayer::new()),
+    },
+    StrategyEntry {
+        name: "discard",
+        description: "always discards the oldest card",
+        options: &[],
+        interactive: false,
+        build: |_| Box::new(DiscardPlayer),
+    },
+    StrategyEntry {
+        name: "play",
+        description: "always plays the oldest card",
+        options: &[],
+        interactive: false,
+        build: |_| Box::new(PlayPlayer),
+    },
+    StrategyEntry {
+        name: "rand_clue",
+        description: "gives random clues",
+        options: &[],
+        interactive: false,
+        build: |_| Box::new(RandCluePlayer),
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyError {
+    UnknownStrategy(String),
+    /// strategy and the option it does not know
+    UnknownOption(String, String),
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownStrategy(name) => {
+                let names: Vec<&str> = STRATEGIES.iter().map(|entry| entry.name).collect();
+                write!(
+                    f,
+                    "unknown strategy {name}, available are {}",
+                    names.join(", ")
+                )
+            }
+            Self::UnknownOption(name, option) => {
+                write!(f, "strategy {name} has no option {option}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StrategyError {}
+
+/// Name and options of a strategy, written as `name[:option...]`
+///
+/// Specs are cheap to clone and send to other threads; every call of
+/// [`StrategySpec::build`] creates a fresh player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrategySpec {
+    name: &'static str,
+    options: Vec<String>,
+}
+
+impl StrategySpec {
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.entry().interactive
+    }
+
+    pub fn build(&self) -> Box<dyn PlayerStrategy> {
+        (self.entry().build)(&self.options)
+    }
+
+    fn entry(&self) -> &'static StrategyEntry {
+        STRATEGIES
+            .iter()
+            .find(|entry| entry.name == self.name)
+            .expect("specs are only created for known strategies")
+    }
+}
+
+impl FromStr for StrategySpec {
+    type Err = StrategyError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut parts = spec.split(':');
+        let name = parts.next().unwrap_or_default();
+        let entry = STRATEGIES
+            .iter()
+            .find(|entry| entry.name == name)
+            .ok_or_else(|| StrategyError::UnknownStrategy(name.to_string()))?;
+        let mut options = Vec::new();
+        for option in parts {
+            if !entry.options.contains(&option) {
+                return Err(StrategyError::UnknownOption(
+                    name.to_string(),
+                    option.to_string(),
+                ));
+            }
+            options.push(option.to_string());
+        }
+        Ok(Self {
+            name: entry.name,
+            options,
+        })
+    }
+}
+
+impl fmt::Display for StrategySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)?;
+        for option in &self.options {
+            write!(f, ":{option}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds one player per seat
+pub fn build_lineup(lineup: &[StrategySpec]) -> Vec<Box<dyn PlayerStrategy>> {
+    lineup.iter().map(StrategySpec::build).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_specs() {
+        let spec: StrategySpec = "hyphenated:debug".parse().expect("known strategy");
+        assert_eq!(spec.name(), "hyphenated");
+        assert_eq!(spec.to_string(), "hyphenated:debug");
+        assert_eq!(spec.build().name(), "hyphenated");
+        assert!(!spec.is_interactive());
+        let human: StrategySpec = "human".parse().expect("registered strategy");
+        assert!(human.is_interactive());
+
+        for entry in STRATEGIES {
+            let spec: StrategySpec = entry.name.parse().expect("registered strategy");
+            assert_eq!(spec.build().name(), entry.name);
+        }
+
+        assert_eq!(
+            "cheater".parse::<StrategySpec>(),
+            Err(StrategyError::UnknownStrategy("cheater".to_string()))
+        );
+        assert_eq!(
+            "discard:debug".parse::<StrategySpec>(),
+            Err(StrategyError::UnknownOption(
+                "discard".to_string(),
+                "debug".to_string()
+            ))
+        );
+    }
+}
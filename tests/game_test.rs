@@ -1,6 +1,11 @@
 mod tester;
 
+use raikan::Variant;
+use std::sync::{Arc, Mutex};
+
 use raikan::game;
+use raikan::hyphenated;
+use raikan::strategies;
 
 #[test]
 fn initial_game() {
@@ -29,6 +34,10 @@ fn discard_on_8_clues() {
     let mut game = game::Game::new(&mut players, false, 234);
     assert_eq!(game.run(&mut players), 0);
     assert_eq!(game.state, game::GameState::Invalid());
+    assert_eq!(
+        game.invalid_move,
+        Some(game::MoveError::DiscardWithMaxClues())
+    );
     assert_eq!(game.status.max_score, 25); // most cards should have been discarded
 }
 
@@ -63,6 +72,7 @@ fn too_many_clues() {
     assert_eq!(game.status.score, 0);
     assert_eq!(game.status.max_score, 25);
     assert_eq!(game.state, game::GameState::Invalid());
+    assert_eq!(game.invalid_move, Some(game::MoveError::NoClueTokens()));
 }
 
 #[test]
@@ -80,6 +90,10 @@ fn unknown_clued_player() {
     assert_eq!(game.status.score, 0);
     assert_eq!(game.status.max_score, 25);
     assert_eq!(game.state, game::GameState::Invalid());
+    assert_eq!(
+        game.invalid_move,
+        Some(game::MoveError::InvalidClueReceiver(2))
+    );
 }
 
 #[test]
@@ -97,4 +111,882 @@ fn clued_self() {
     assert_eq!(game.status.score, 0);
     assert_eq!(game.status.max_score, 25);
     assert_eq!(game.state, game::GameState::Invalid());
+    assert_eq!(
+        game.invalid_move,
+        Some(game::MoveError::InvalidClueReceiver(0))
+    );
+}
+
+#[test]
+fn try_execute_rejects_invalid_moves() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Discard(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Discard(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let mut game = game::Game::new(&mut players, false, 234);
+    assert_eq!(
+        game.validate_move(game::Move::Play(5)),
+        Err(game::MoveError::PositionOutOfRange(5))
+    );
+    assert_eq!(
+        game.validate_move(game::Move::Clue(
+            1,
+            game::Clue::Color(game::ClueColor::Teal())
+        )),
+        Err(game::MoveError::InvalidClue(game::Clue::Color(
+            game::ClueColor::Teal()
+        )))
+    );
+    assert_eq!(
+        game.try_execute(game::Move::Discard(0), &mut players),
+        Err(game::MoveError::DiscardWithMaxClues())
+    );
+    // rejected moves leave the game untouched
+    assert_eq!(game.state, game::GameState::Early());
+    assert_eq!(game.status.turn, 0);
+    assert_eq!(game.invalid_move, None);
+    assert_eq!(game.try_execute(game::Move::Play(0), &mut players), Ok(()));
+    assert_eq!(game.status.turn, 1);
+}
+
+#[test]
+fn legal_moves_are_valid() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester3 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+    players.push(&mut tester3);
+
+    let mut game = game::Game::new(&mut players, false, 3);
+    for _ in 0..6 {
+        let moves = game.legal_moves();
+        assert_eq!(
+            moves.contains(&game::Move::Discard(0)),
+            game.status.clues < 8
+        );
+        assert!(moves.contains(&game::Move::Play(4)));
+        for player in 1..3 {
+            for rank in 1..=5 {
+                let clue = game::Move::Clue(player, game::Clue::Rank(rank));
+                assert_eq!(moves.contains(&clue), game.validate_move(clue).is_ok());
+            }
+        }
+        assert!(moves.iter().all(|m| game.validate_move(*m).is_ok()));
+        game.try_execute(moves[moves.len() - 1], &mut players)
+            .expect("legal move should be valid");
+    }
+    assert_eq!(game.status.clues, 2);
+}
+
+#[test]
+fn step_by_step() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let mut game = game::Game::new(&mut players, false, 1238);
+    assert_eq!(game.active_player(), 0);
+    assert_eq!(game.step(&mut players), Some(game::Move::Play(0)));
+    assert_eq!(game.status.turn, 1);
+    assert_eq!(game.active_player(), 1);
+    // inject a move instead of asking the strategy
+    let clue = *game
+        .legal_moves()
+        .iter()
+        .find(|m| matches!(m, game::Move::Clue(_, _)))
+        .expect("some clue should be possible");
+    game.try_execute(clue, &mut players)
+        .expect("clue should be valid");
+    assert_eq!(game.status.clues, 7);
+    assert_eq!(game.active_player(), 0);
+    let mut turns = 2;
+    while game.step(&mut players).is_some() {
+        turns += 1;
+    }
+    assert!(game.is_over());
+    assert_eq!(game.state, game::GameState::Lost());
+    assert_eq!(game.status.turn, turns);
+    assert_eq!(game.step(&mut players), None);
+}
+
+#[test]
+fn mixed_strategy_lineup() {
+    let lineup: Vec<strategies::StrategySpec> = ["hyphenated", "discard", "rand_clue", "play"]
+        .iter()
+        .map(|name| name.parse().expect("registered strategy"))
+        .collect();
+    for seed in 0..20 {
+        let mut strategies = strategies::build_lineup(&lineup);
+        let game = game::Game::play_seed(&mut strategies, seed);
+        assert!(game.is_over());
+        assert_ne!(game.state, game::GameState::Invalid(), "seed {seed}");
+    }
+}
+
+#[test]
+fn misplays_are_explained() {
+    let mut explained = 0;
+    for seed in 0..20 {
+        let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 4];
+        let game = game::Game::play_seed(&mut bots, seed);
+        assert_eq!(game.misplays.len(), game.status.num_strikes as usize);
+        assert!(game.misplays.iter().all(|misplay| misplay.report.is_some()));
+        explained += game.misplays.len();
+    }
+    assert!(explained > 0);
+
+    // strategies without beliefs don't explain their plays
+    let mut play1 = raikan::dump_strategies::PlayPlayer;
+    let mut play2 = raikan::dump_strategies::PlayPlayer;
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = vec![&mut play1, &mut play2];
+    let mut game = game::Game::new(&mut players, false, 0);
+    game.run(&mut players);
+    assert_eq!(game.misplays.len(), 3);
+    assert!(game.misplays.iter().all(|misplay| misplay.report.is_none()));
+}
+
+#[test]
+fn snapshot_and_rewind() {
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 3];
+    let mut players = game::seats(&mut bots);
+    let mut game = game::Game::new(&mut players, false, 42);
+    for _ in 0..10 {
+        game.step(&mut players);
+    }
+    drop(players);
+    let snapshot = game.snapshot();
+    let saved_bots = bots.clone();
+    assert_eq!(snapshot.turn(), 10);
+
+    let mut players = game::seats(&mut bots);
+    let score = game.run(&mut players);
+    let replay = game.replay_url();
+    drop(players);
+
+    // branches do not influence the snapshot
+    let mut branch = snapshot.branch();
+    let mut branch_bots = saved_bots.clone();
+    let mut players = game::seats(&mut branch_bots);
+    let first_move = branch.legal_moves()[0];
+    branch
+        .try_execute(first_move, &mut players)
+        .expect("legal move should be valid");
+    assert_eq!(branch.status.turn, 11);
+    drop(players);
+
+    game.restore(&snapshot);
+    bots = saved_bots;
+    assert_eq!(game.status.turn, 10);
+    assert!(!game.is_over());
+    let mut players = game::seats(&mut bots);
+    assert_eq!(game.run(&mut players), score);
+    assert_eq!(game.replay_url(), replay);
+}
+
+fn sorted_deck(variant: &Variant, without: &[game::Card]) -> Vec<game::Card> {
+    let mut deck = Vec::new();
+    for suit in variant.suits() {
+        for rank in 1..=5 {
+            let card = game::Card { suit: *suit, rank };
+            let removed = without.iter().filter(|other| **other == card).count() as u8;
+            for _ in removed..variant.card_count(&card) {
+                deck.push(card);
+            }
+        }
+    }
+    deck
+}
+
+#[test]
+fn game_from_explicit_deck() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let deck = sorted_deck(&Variant::no_variant(), &[]);
+    let game = game::GameBuilder::new(deck)
+        .build(&mut players)
+        .expect("deck should be valid");
+    assert_eq!(game.state, game::GameState::Early());
+    assert!(
+        game.replay_url()
+            .expect("replay should be available")
+            .starts_with("https://hanab.live/replay-json/215aaabbccdde")
+    );
+}
+
+#[test]
+fn game_from_mid_game_position() {
+    let played = [
+        game::Card {
+            suit: game::Suit::Red(),
+            rank: 1,
+        },
+        game::Card {
+            suit: game::Suit::Red(),
+            rank: 2,
+        },
+        game::Card {
+            suit: game::Suit::Yellow(),
+            rank: 1,
+        },
+    ];
+    let discarded = [game::Card {
+        suit: game::Suit::Blue(),
+        rank: 5,
+    }];
+    let mut deck = sorted_deck(
+        &Variant::no_variant(),
+        &[&played[..], &discarded[..]].concat(),
+    );
+    deck.reverse();
+
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 3];
+    let mut players = game::seats(&mut bots);
+    let mut game = game::GameBuilder::new(deck)
+        .played(played)
+        .discarded(discarded)
+        .clues(3)
+        .strikes(1)
+        .known_clue(0, 1, game::Clue::Rank(1))
+        .build(&mut players)
+        .expect("position should be valid");
+    assert_eq!(game.state, game::GameState::Mid());
+    assert_eq!(game.status.score, 3);
+    assert_eq!(game.status.max_score, 24);
+    assert_eq!(game.status.clues, 3);
+    assert_eq!(game.status.num_strikes, 1);
+    assert_eq!(game.replay_url(), None);
+    game.run(&mut players);
+    assert!(game.is_over());
+    assert_ne!(game.state, game::GameState::Invalid());
+}
+
+#[test]
+fn invalid_game_setups() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let r1 = game::Card {
+        suit: game::Suit::Red(),
+        rank: 1,
+    };
+    let r3 = game::Card {
+        suit: game::Suit::Red(),
+        rank: 3,
+    };
+    let deck = sorted_deck(&Variant::no_variant(), &[r1, r3]);
+    assert_eq!(
+        game::GameBuilder::new(deck.clone())
+            .played([r1, r3])
+            .build(&mut players)
+            .err(),
+        Some(game::SetupError::NotPlayable(r3))
+    );
+    assert_eq!(
+        game::GameBuilder::new(deck.clone())
+            .played([r1, r1])
+            .build(&mut players)
+            .err(),
+        Some(game::SetupError::TooManyCopies(r1))
+    );
+    assert_eq!(
+        game::GameBuilder::new(deck[..9].to_vec())
+            .build(&mut players)
+            .err(),
+        Some(game::SetupError::DeckTooSmall())
+    );
+    assert_eq!(
+        game::GameBuilder::new(deck.clone())
+            .known_clue(1, 1, game::Clue::Rank(1))
+            .build(&mut players)
+            .err(),
+        Some(game::SetupError::InvalidClue(
+            game::MoveError::InvalidClueReceiver(1)
+        ))
+    );
+    assert_eq!(
+        game::GameBuilder::new(deck.clone())
+            .known_clue(2, 0, game::Clue::Rank(1))
+            .build(&mut players)
+            .err(),
+        Some(game::SetupError::InvalidClueGiver(2))
+    );
+    assert_eq!(
+        game::GameBuilder::new(deck.clone())
+            .build(&mut players[..1])
+            .err(),
+        Some(game::SetupError::InvalidPlayerCount(1))
+    );
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 7];
+    assert_eq!(
+        game::GameBuilder::new(deck)
+            .build(&mut game::seats(&mut bots))
+            .err(),
+        Some(game::SetupError::InvalidPlayerCount(7))
+    );
+}
+
+#[test]
+fn hanabi_live_json_round_trip() {
+    for seed in [3, 42] {
+        let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 3];
+        let game = game::Game::play_seed(&mut bots, seed);
+        let json = game.replay_json().expect("replay should be available");
+
+        let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 3];
+        let mut players = game::seats(&mut bots);
+        let replayed =
+            game::Game::from_hanabi_live_json(&json, &mut players).expect("replay should be valid");
+        assert_eq!(replayed.state, game.state);
+        assert_eq!(replayed.status.score, game.status.score);
+        assert_eq!(replayed.replay_json(), Some(json));
+    }
+}
+
+#[test]
+fn write_replay_file() {
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 2];
+    let game = game::Game::play_seed(&mut bots, 11);
+    let path = std::env::temp_dir().join(format!("raikan-replay-{}.json", std::process::id()));
+    game.write_replay(&path).expect("replay should be writable");
+    let json = std::fs::read_to_string(&path).expect("replay should be readable");
+    std::fs::remove_file(&path).expect("replay should be removable");
+    assert!(json.starts_with(r#"{"players":["hyphenated 1","hyphenated 2"],"#));
+    assert_eq!(Some(json), game.replay_json());
+}
+
+#[test]
+fn terminated_hanabi_live_game() {
+    let json = r#"{
+        "id": 12345,
+        "players": ["alice", "bob"],
+        "deck": [
+            {"suitIndex": 0, "rank": 1}, {"suitIndex": 0, "rank": 1}, {"suitIndex": 0, "rank": 1},
+            {"suitIndex": 0, "rank": 2}, {"suitIndex": 0, "rank": 2}, {"suitIndex": 0, "rank": 3},
+            {"suitIndex": 0, "rank": 3}, {"suitIndex": 0, "rank": 4}, {"suitIndex": 0, "rank": 4},
+            {"suitIndex": 0, "rank": 5}, {"suitIndex": 1, "rank": 1}, {"suitIndex": 1, "rank": 1}
+        ],
+        "actions": [
+            {"type": 0, "target": 0},
+            {"type": 3, "target": 0, "value": 1},
+            {"type": 4, "target": 0, "value": 4}
+        ],
+        "options": {"variant": "No Variant"},
+        "notes": [[], []]
+    }"#;
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let game =
+        game::Game::from_hanabi_live_json(json, &mut players).expect("replay should be valid");
+    assert_eq!(game.status.score, 1);
+    assert_eq!(game.status.clues, 7);
+    assert_eq!(
+        game.state,
+        game::GameState::Terminated(game::END_TERMINATED)
+    );
+    let exported = game.replay_json().expect("replay should be available");
+    assert!(exported.contains(r#""players":["alice","bob"]"#));
+    assert!(exported.contains(r#"{"type":4,"target":0,"value":4}]"#));
+
+    assert_eq!(
+        game::Game::from_hanabi_live_json(json, &mut players[..1]).err(),
+        Some(game::ReplayError::PlayerCount(2))
+    );
+    assert_eq!(
+        game::Game::from_hanabi_live_json(
+            &json.replace(r#"{"type": 0, "target": 0}"#, r#"{"type": 0, "target": 7}"#),
+            &mut players
+        )
+        .err(),
+        Some(game::ReplayError::InvalidAction(0))
+    );
+    // clue for a player not in the game
+    assert_eq!(
+        game::Game::from_hanabi_live_json(
+            &json.replace(
+                r#"{"type": 3, "target": 0, "value": 1}"#,
+                r#"{"type": 3, "target": 253, "value": 1}"#
+            ),
+            &mut players
+        )
+        .err(),
+        Some(game::ReplayError::InvalidAction(1))
+    );
+}
+
+#[test]
+fn replay_urls_with_options() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    // hanab.live appends the option flags to the variant id (here: empty clues)
+    let url = "https://hanab.live/replay-json/215aaabbccddefffgg,05aapa,0e";
+    let game = game::Game::from_replay_url(url, &mut players).expect("url should be valid");
+    assert_eq!(
+        game.options,
+        game::GameOptions {
+            empty_clues: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(game.status.score, 1);
+    assert!(
+        game.replay_url()
+            .expect("replay should be available")
+            .ends_with(",05aapa,0e")
+    );
+
+    for (flags, option) in [
+        ("d", "deck plays"),
+        ("a", "all or nothing"),
+        ("ec", "detrimental characters"),
+    ] {
+        let url = format!("https://hanab.live/replay-json/215aaabbccddefffgg,05aapa,0{flags}");
+        assert_eq!(
+            game::Game::from_replay_url(&url, &mut players).err(),
+            Some(game::ReplayError::InvalidSetup(
+                game::SetupError::UnsupportedOption(option)
+            )),
+            "{url}"
+        );
+    }
+}
+
+#[test]
+fn hand_sizes() {
+    // hanab.live deals 4 cards with 4 and 5 players (this simulator used to deal 5 with 5 players)
+    let options = game::GameOptions::default();
+    assert_eq!(
+        (2..=6)
+            .map(|num_players| options.hand_size(num_players))
+            .collect::<Vec<_>>(),
+        vec![5, 5, 4, 4, 3]
+    );
+
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 5];
+    let mut players = game::seats(&mut bots);
+    let game = game::Game::new(&mut players, false, 3);
+    for player in 0..5 {
+        assert_eq!(game.num_hand_cards(player), 4);
+    }
+}
+
+#[test]
+fn replay_url_round_trip() {
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 4];
+    let game = game::Game::play_seed(&mut bots, 7);
+    let url = game.replay_url().expect("replay should be available");
+
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 4];
+    let mut players = game::seats(&mut bots);
+    let replayed = game::Game::from_replay_url(&url, &mut players).expect("url should be valid");
+    assert_eq!(replayed.state, game.state);
+    assert_eq!(replayed.status.score, game.status.score);
+    assert_eq!(replayed.replay_url(), Some(url));
+}
+
+#[test]
+fn invalid_replay_urls() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    // a play and a rank 1 clue, encoded with different action type ranges
+    let deck = "215aaabbccddefffgg";
+    for actions in ["05aapa", "03aala"] {
+        let url = format!("https://hanab.live/replay-json/{deck},{actions},0");
+        let game = game::Game::from_replay_url(&url, &mut players).expect("url should be valid");
+        assert_eq!(game.status.score, 1);
+        assert_eq!(game.status.clues, 7);
+    }
+
+    for (url, error) in [
+        (
+            "215aaabbccddefffgg,05aa",
+            game::ReplayError::InvalidUrl(
+                "expected deck, actions and options separated by commas".to_string(),
+            ),
+        ),
+        (
+            "215aaabbccddefffgg,05aa,999",
+            game::ReplayError::UnknownVariantId(999),
+        ),
+        (
+            "215aaabbccddefffgg,05aa,0z",
+            game::ReplayError::UnknownOptions("z".to_string()),
+        ),
+        (
+            "215aaabbccddefffgg,05a,0",
+            game::ReplayError::InvalidUrl("the target of the last action is missing".to_string()),
+        ),
+        (
+            "215aa-bbccddefffgg,05aa,0",
+            game::ReplayError::InvalidUrl("'-' is not a base62 character".to_string()),
+        ),
+        (
+            "315aaabbccddefffgg,05aa,0",
+            game::ReplayError::PlayerCount(3),
+        ),
+        (
+            "215aaabbccd,05aa,0",
+            game::ReplayError::InvalidSetup(game::SetupError::DeckTooSmall()),
+        ),
+        (
+            "215aaabbccddefffgg,05az,0",
+            game::ReplayError::InvalidAction(0),
+        ),
+        // clue for player 7 in a two player game
+        (
+            "215aaabbccddefffgg,05aaph,0",
+            game::ReplayError::InvalidAction(1),
+        ),
+        // game ended by player 7
+        (
+            "215aaabbccddefffgg,05aaeh,0",
+            game::ReplayError::InvalidAction(1),
+        ),
+    ] {
+        assert_eq!(
+            game::Game::from_replay_url(url, &mut players).err(),
+            Some(error),
+            "{url}"
+        );
+    }
+}
+
+#[test]
+fn player_view() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let mut game = game::Game::new(&mut players, false, 234);
+    let clue = *game
+        .legal_moves()
+        .iter()
+        .find(|m| matches!(m, game::Move::Clue(_, _)))
+        .expect("some clue should be possible");
+    let game::Move::Clue(_player, given_clue) = clue else {
+        unreachable!()
+    };
+    game.try_execute(clue, &mut players)
+        .expect("clue should be valid");
+
+    let view = game.player_view();
+    assert_eq!(view.active_player, 1);
+    assert_eq!(view.num_players(), 2);
+    assert_eq!(view.deck_size, 40);
+    assert_eq!(view.status.clues, 7);
+    assert_eq!(view.played, &[0; 5]);
+    assert!(view.hands[0].iter().all(|card| card.card.is_none()));
+    assert!(view.hands[1].iter().all(|card| card.card.is_some()));
+    assert!(view.hands[0].iter().any(|card| card.clued));
+    for card in view.hands[0].iter() {
+        assert_eq!(card.clues, &[(given_clue, card.clued)]);
+    }
+    assert!(view.hands[1].iter().all(|card| card.clues.is_empty()));
+}
+
+#[derive(Default)]
+struct EventRecorder {
+    events: Vec<(u8, game::GameEvent)>,
+}
+
+impl game::GameObserver for EventRecorder {
+    fn event(&mut self, status: &game::GameStatus, event: &game::GameEvent) {
+        self.events.push((status.turn, *event));
+    }
+}
+
+#[test]
+fn observe_game_events() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let recorder = Arc::new(Mutex::new(EventRecorder::default()));
+    let mut game = game::GameBuilder::new(sorted_deck(&Variant::no_variant(), &[]))
+        .observer(recorder.clone())
+        .build(&mut players)
+        .expect("deck should be valid");
+    game.run(&mut players);
+    assert_eq!(game.state, game::GameState::Lost());
+
+    let events = &recorder.lock().unwrap().events;
+    let r2 = game::Card {
+        suit: game::Suit::Red(),
+        rank: 2,
+    };
+    // the sorted deck deals r1 r1 r1 r2 r2 to the first player, newest card first
+    assert_eq!(
+        events[4],
+        (
+            0,
+            game::GameEvent::Drawn {
+                player: 0,
+                card: r2
+            }
+        )
+    );
+    assert_eq!(
+        events
+            .iter()
+            .filter(|(_, event)| matches!(event, game::GameEvent::Drawn { .. }))
+            .count(),
+        10 + game.status.turn as usize - 1
+    );
+    assert_eq!(
+        events[10..13],
+        [
+            (
+                1,
+                game::GameEvent::Misplayed {
+                    player: 0,
+                    pos: 0,
+                    card: r2
+                }
+            ),
+            (1, game::GameEvent::Strike { num_strikes: 1 }),
+            (
+                1,
+                game::GameEvent::Drawn {
+                    player: 0,
+                    card: game::Card {
+                        suit: game::Suit::Yellow(),
+                        rank: 1
+                    }
+                }
+            ),
+        ]
+    );
+    let strikes: Vec<_> = events
+        .iter()
+        .filter_map(|(_, event)| match event {
+            game::GameEvent::Strike { num_strikes } => Some(*num_strikes),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(strikes, vec![1, 2, 3]);
+    assert_eq!(
+        events[events.len() - 2].1,
+        game::GameEvent::StateChanged {
+            from: game::GameState::Early(),
+            to: game::GameState::Lost()
+        }
+    );
+    assert_eq!(
+        events[events.len() - 1].1,
+        game::GameEvent::GameOver {
+            state: game::GameState::Lost()
+        }
+    );
+}
+
+#[test]
+fn branches_detach_observers() {
+    let mut bots = vec![hyphenated::HyphenatedPlayer::new(false); 2];
+    let recorder = Arc::new(Mutex::new(EventRecorder::default()));
+    let mut game = {
+        let mut players = game::seats(&mut bots);
+        game::GameBuilder::new(sorted_deck(&Variant::no_variant(), &[]))
+            .observer(recorder.clone())
+            .build(&mut players)
+            .expect("deck should be valid")
+    };
+    let snapshot = game.snapshot();
+    let recorded = recorder.lock().unwrap().events.len();
+
+    let mut branch_bots = bots.clone();
+    let mut players = game::seats(&mut branch_bots);
+    let mut branch = snapshot.branch();
+    branch.step(&mut players);
+    assert_eq!(recorder.lock().unwrap().events.len(), recorded);
+
+    let mut players = game::seats(&mut bots);
+    game.step(&mut players);
+    game.restore(&snapshot);
+    let recorded = recorder.lock().unwrap().events.len();
+    game.step(&mut players);
+    assert!(recorder.lock().unwrap().events.len() > recorded);
+}
+
+#[test]
+fn terminate_game() {
+    let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+    let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+    players.push(&mut tester1);
+    players.push(&mut tester2);
+
+    let recorder = Arc::new(Mutex::new(EventRecorder::default()));
+    let mut game = game::GameBuilder::new(sorted_deck(&Variant::no_variant(), &[]))
+        .observer(recorder.clone())
+        .build(&mut players)
+        .expect("deck should be valid");
+    game.step(&mut players);
+    game.terminate(1, game::END_TERMINATED);
+    let state = game::GameState::Terminated(game::END_TERMINATED);
+    assert_eq!(game.state, state);
+    assert!(game.is_over());
+    assert_eq!(
+        recorder.lock().unwrap().events.last(),
+        Some(&(1, game::GameEvent::GameOver { state }))
+    );
+    assert!(
+        game.replay_json()
+            .expect("replay should be available")
+            .contains(r#"{"type":4,"target":1,"value":4}]"#)
+    );
+
+    // finished games stay as they are
+    game.terminate(0, game::END_TERMINATED);
+    assert_eq!(game.state, state);
+}
+
+#[test]
+fn six_suit_game() {
+    use raikan::hyphenated::HyphenatedPlayer;
+
+    let variant = raikan::Variant::from_name("6 Suits").expect("known variant");
+    let mut h1 = HyphenatedPlayer::new(false);
+    let mut h2 = HyphenatedPlayer::new(false);
+    let mut h3 = HyphenatedPlayer::new(false);
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = vec![&mut h1, &mut h2, &mut h3];
+
+    let mut game = game::Game::with_variant(&mut players, variant, false, 4242);
+    assert_eq!(game.status.max_score, 30);
+    let score = game.run(&mut players);
+    assert!(score > 0);
+
+    // the replay url must carry the variant and all six suits:
+    let url = game.replay_url().expect("6 Suits has a hanab.live id");
+    let encoded = url.trim_start_matches("https://hanab.live/replay-json/");
+    let parts: Vec<&str> = encoded.split(',').collect();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[2], "1");
+
+    let mut r1 = HyphenatedPlayer::new(false);
+    let mut r2 = HyphenatedPlayer::new(false);
+    let mut r3 = HyphenatedPlayer::new(false);
+    let mut replayers: Vec<&mut dyn game::PlayerStrategy> = vec![&mut r1, &mut r2, &mut r3];
+    let replayed = game::Game::from_replay(
+        game.status.turn,
+        parts[0],
+        parts[1],
+        parts[2],
+        &mut replayers,
+    )
+    .expect("replay should be valid");
+    assert_eq!(replayed.variant, variant);
+    assert_eq!(replayed.status.score, game.status.score);
+}
+
+#[test]
+fn one_extra_card_game() {
+    use raikan::hyphenated::HyphenatedPlayer;
+
+    let options = game::GameOptions {
+        one_extra_card: true,
+        ..Default::default()
+    };
+    let mut hyphenated: Vec<HyphenatedPlayer> =
+        (0..5).map(|_| HyphenatedPlayer::new(false)).collect();
+    let mut players = game::seats(&mut hyphenated);
+
+    let mut game = game::Game::with_options(
+        &mut players,
+        raikan::Variant::no_variant(),
+        options,
+        false,
+        77,
+    )
+    .expect("supported options");
+    assert_eq!(game.num_hand_cards(0), 5);
+    game.run(&mut players);
+
+    let url = game.replay_url().expect("No Variant has a hanab.live id");
+    let encoded = url.trim_start_matches("https://hanab.live/replay-json/");
+    let parts: Vec<&str> = encoded.split(',').collect();
+    assert_eq!(parts[2], "0x");
+
+    let mut replaying: Vec<HyphenatedPlayer> =
+        (0..5).map(|_| HyphenatedPlayer::new(false)).collect();
+    let mut replayers = game::seats(&mut replaying);
+    let replayed = game::Game::from_replay(
+        game.status.turn,
+        parts[0],
+        parts[1],
+        parts[2],
+        &mut replayers,
+    )
+    .expect("replay should be valid");
+    assert_eq!(replayed.options, options);
+    assert_eq!(replayed.status.score, game.status.score);
+}
+
+#[test]
+fn empty_clues() {
+    for (empty_clues, expected_state) in [
+        (false, game::GameState::Invalid()),
+        (true, game::GameState::Lost()),
+    ] {
+        let mut tester1 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+        tester1.add(game::Move::Clue(1, game::Clue::Rank(5)));
+        let mut tester2 = tester::InstructedPlayer::with_default(game::Move::Play(0));
+
+        let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
+        players.push(&mut tester1);
+        players.push(&mut tester2);
+
+        let options = game::GameOptions {
+            empty_clues,
+            ..Default::default()
+        };
+        let mut game = game::Game::with_options(
+            &mut players,
+            raikan::Variant::no_variant(),
+            options,
+            false,
+            1,
+        )
+        .expect("supported options");
+        game.run(&mut players);
+        assert_eq!(game.state, expected_state);
+    }
 }
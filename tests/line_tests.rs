@@ -1,7 +1,7 @@
 use colored::*;
 use raikan::{
-    self, PositionSet,
-    game::{self, ClueColor, Game, PlayerStrategy},
+    self, PositionSet, Variant,
+    game::{self, ClueColor, Game},
     hyphenated::{self, HyphenatedPlayer, LineScore, Slot},
 };
 
@@ -77,34 +77,28 @@ fn replay_from_deck(deck: &str) -> Replay {
     ];
     let compact_deck = deck.replace(' ', "");
     let mut compact_chars = compact_deck.chars();
-    for drawing_player in 0..4 {
-        for _num_card in 0..4 {
-            let suit = match compact_chars
-                .next()
-                .expect("deck is too short for initial draws")
-            {
-                'r' => game::Suit::Red(),
-                'b' => game::Suit::Blue(),
-                'y' => game::Suit::Yellow(),
-                'g' => game::Suit::Green(),
-                'p' => game::Suit::Purple(),
-                _ => unimplemented!("Unknown suit color"),
-            };
-            let rank = compact_chars
-                .next()
-                .expect("deck is too short for initial draws")
-                .to_digit(10)
-                .expect("card rank must be a digit between 1 and 5") as u8;
-            let card = game::Card { rank, suit };
-            for notifying_player in 0..4 {
-                if notifying_player == drawing_player {
-                    players[notifying_player].own_drawn();
-                } else {
-                    players[notifying_player]
-                        .drawn((4 + drawing_player - notifying_player) % 4, card);
-                }
-            }
-        }
+    let mut cards = Vec::new();
+    while let Some(suit_char) = compact_chars.next() {
+        let suit = match suit_char {
+            'r' => game::Suit::Red(),
+            'b' => game::Suit::Blue(),
+            'y' => game::Suit::Yellow(),
+            'g' => game::Suit::Green(),
+            'p' => game::Suit::Purple(),
+            _ => unimplemented!("Unknown suit color"),
+        };
+        let rank = compact_chars
+            .next()
+            .expect("card rank is missing")
+            .to_digit(10)
+            .expect("card rank must be a digit between 1 and 5") as u8;
+        cards.push(game::Card { rank, suit });
+    }
+    {
+        let mut strategies = game::seats(&mut players);
+        game::GameBuilder::new(cards)
+            .build(&mut strategies)
+            .expect("deck should be valid");
     }
     let lines = [
         players[0].line(),
@@ -137,13 +131,9 @@ fn replay_game(turn: u8, deck: &str, actions: &str, options: &str) -> Replay {
     let mut h2 = HyphenatedPlayer::with_logger(log.new(o!("player" => "Bob")));
     let mut h3 = HyphenatedPlayer::with_logger(log.new(o!("player" => "Cathy")));
     let mut h4 = HyphenatedPlayer::with_logger(log.new(o!("player" => "Donald")));
-    let mut players: Vec<&mut dyn game::PlayerStrategy> = Vec::new();
-    players.push(&mut h1);
-    players.push(&mut h2);
-    players.push(&mut h3);
-    players.push(&mut h4);
+    let mut players: Vec<&mut dyn game::PlayerStrategy> = vec![&mut h1, &mut h2, &mut h3, &mut h4];
 
-    Game::from_replay(turn, deck, actions, options, &mut players);
+    Game::from_replay(turn, deck, actions, options, &mut players).expect("replay should be valid");
 
     let target_player = (turn) % num_players;
     println!("target player: {target_player}");
@@ -294,7 +284,7 @@ fn clue(line: &hyphenated::Line, player: usize, clue: game::Clue) -> hyphenated:
 fn safe_5s() {
     let mut line = hyphenated::Line::new(4, 0);
     let game = game::Game::empty(4);
-    let suits = game.suits.clone();
+    let suits = game.variant.suits();
     line.own_drawn();
     line.own_drawn();
     line.own_drawn();
@@ -306,7 +296,7 @@ fn safe_5s() {
     println!("line: {:?}", line);
 
     let mut color_line = line.clone();
-    let color_safe = color_line.clue(1, game::Clue::Color(suits[3].clue_color()));
+    let color_safe = color_line.clue(1, game::Clue::Color(suits[3].clue_color().unwrap()));
     assert_ne!(color_safe, None);
     let mut rank_line = line.clone();
     let rank_safe = rank_line.clue(1, game::Clue::Rank(5));
@@ -411,7 +401,7 @@ fn track_cards() {
 fn dont_bad_touch_same_card1() {
     let mut line = hyphenated::Line::new(4, 0);
     let game = game::Game::empty(4);
-    let suits = game.suits.clone();
+    let suits = game.variant.suits();
     line.own_drawn();
     line.own_drawn();
     line.own_drawn();
@@ -428,7 +418,7 @@ fn dont_bad_touch_same_card1() {
     let rank_clue = rank_line.clue(1, game::Clue::Rank(1));
     assert_ne!(rank_clue, None);
     let mut color_line = line.clone();
-    let color_clue = color_line.clue(1, game::Clue::Color(suits[3].clue_color()));
+    let color_clue = color_line.clue(1, game::Clue::Color(suits[3].clue_color().unwrap()));
     assert_ne!(color_clue, None);
     println!("Rank\n line: {:?}\n score: {:?}", rank_line, rank_clue);
     println!("color\n line: {:?}\n score: {:?}", color_line, color_clue);
@@ -961,6 +951,8 @@ fn discard_double_card() {
 
 // delayed play clues:
 #[test]
+// `0100` is a decimal literal, the position set keeps only its lowest 4 bits
+#[allow(clippy::zero_prefixed_literal)]
 fn unambiguous_delayed_play_clue_by_color() {
     let mut line = hyphenated::Line::new(4, 0);
     line.own_drawn();
@@ -1019,6 +1011,8 @@ fn unambiguous_delayed_play_clue_by_color() {
 }
 
 #[test]
+// `0100` is a decimal literal, the position set keeps only its lowest 4 bits
+#[allow(clippy::zero_prefixed_literal)]
 fn ambiguous_delayed_play_clue_by_rank() {
     let mut line = hyphenated::Line::new(4, 0);
     line.own_drawn();
@@ -1727,6 +1721,7 @@ fn prefer_finess_over_delayed_play_clue() {
 
 #[test]
 #[ignore]
+#[allow(clippy::assertions_on_constants)]
 fn layed_finess() {
     // https://hanab.live/replay-json/415gbbkuaxamlmfipdgchyaejwukhfvqlrpvnpkwsrfitcdqnusxo,05pbah0danocafalvavdae1dapaaoduaaradbgibamacaqbiavabwa,0
     // e.g. on r3 to get g1, r2 to play
@@ -1769,6 +1764,7 @@ fn dont_force_players_into_chopping_critical_cards() {
 
 #[test]
 #[ignore]
+#[allow(clippy::assertions_on_constants)]
 fn fix_pending_misplay() {
     // https://hanab.live/replay-json/415gbbkuaxamlmfipdgchyaejwukhfvqlrpvnpkwsrfitcdqnusxo,05pbah0danocafalvavdae1dapaaoduaaradbgibamacaqbiavabwa,0
     // turn
@@ -2254,6 +2250,7 @@ fn never_extend_hard_quantum_via_callbacks3() {
 
 #[test]
 #[ignore]
+#[allow(clippy::assertions_on_constants)]
 fn stop_finess_plays_after_prompt_misplays() {
     let _replay = replay_game(
         19,
@@ -2432,3 +2429,341 @@ fn good_touch_principal4() {
 
     assert!(y3 > y2_dub);
 }
+
+fn variant_line(variant_name: &str, hands: [[(game::Suit, u8); 4]; 3]) -> hyphenated::Line {
+    let variant = Variant::from_name(variant_name).expect("variant should be known");
+    let mut line =
+        hyphenated::Line::with_logger(4, 0, variant, game::GameOptions::default(), get_logger());
+    for _ in 0..4 {
+        line.own_drawn();
+    }
+    for (player, hand) in hands.iter().enumerate() {
+        for (suit, rank) in hand.iter().rev() {
+            line.drawn(
+                player + 1,
+                game::Card {
+                    suit: *suit,
+                    rank: *rank,
+                },
+            );
+        }
+    }
+    line
+}
+
+#[test]
+fn rainbow_is_touched_by_color_clues() {
+    use game::Suit::*;
+    let mut line = variant_line(
+        "Rainbow (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Green(), 4)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 5), (Green(), 5)],
+            [(Red(), 3), (Red(), 4), (Rainbow(), 3), (Rainbow(), 4)],
+        ],
+    );
+    line.clued(
+        1,
+        0,
+        game::Clue::Color(ClueColor::Red()),
+        PositionSet::create(4, 0b0001),
+    );
+    println!("line: {line:?}");
+    let focus = line.hands.slot(0, 0).quantum;
+    assert_eq!(focus.size(), 2);
+    assert!(focus.contains(&game::Card {
+        suit: Red(),
+        rank: 1
+    }));
+    assert!(focus.contains(&game::Card {
+        suit: Rainbow(),
+        rank: 1
+    }));
+    for pos in 1..4 {
+        let quantum = line.hands.slot(0, pos).quantum;
+        assert!(
+            quantum
+                .iter()
+                .all(|card| card.suit != Red() && card.suit != Rainbow()),
+            "untouched slot {pos} can't be red or rainbow: {quantum}"
+        );
+    }
+}
+
+#[test]
+fn pink_is_touched_by_rank_clues() {
+    use game::Suit::*;
+    let mut line = variant_line(
+        "Pink (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Green(), 4)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 5), (Green(), 5)],
+            [(Red(), 3), (Red(), 4), (Pink(), 3), (Pink(), 4)],
+        ],
+    );
+    line.clued(1, 0, game::Clue::Rank(2), PositionSet::create(4, 0b0011));
+    println!("line: {line:?}");
+    let second = line.hands.slot(0, 1).quantum;
+    assert!(second.contains(&game::Card {
+        suit: Yellow(),
+        rank: 2
+    }));
+    assert!(second.contains(&game::Card {
+        suit: Pink(),
+        rank: 5
+    }));
+    assert!(!second.contains(&game::Card {
+        suit: Yellow(),
+        rank: 5
+    }));
+    for pos in 2..4 {
+        let quantum = line.hands.slot(0, pos).quantum;
+        assert!(
+            quantum
+                .iter()
+                .all(|card| card.suit != Pink() && card.rank != 2),
+            "untouched slot {pos} can't be pink or a 2: {quantum}"
+        );
+    }
+}
+
+#[test]
+fn save_brown_5_with_color() {
+    use game::Suit::*;
+    let line = variant_line(
+        "Brown (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Brown(), 5)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 4), (Green(), 4)],
+            [(Red(), 3), (Red(), 4), (Brown(), 3), (Brown(), 4)],
+        ],
+    );
+    assert!(line.clone().clue(1, game::Clue::Rank(5)).is_none());
+    let score = clue(&line, 1, game::Clue::Color(ClueColor::Brown()));
+    assert!(!score.has_errors());
+}
+
+#[test]
+fn save_black_cards_from_chop() {
+    use game::Suit::*;
+    let line = variant_line(
+        "Black (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Black(), 3)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 4), (Green(), 4)],
+            [(Red(), 3), (Red(), 4), (Red(), 2), (Green(), 2)],
+        ],
+    );
+    let no_clue = line.score(0);
+    for save in [game::Clue::Color(ClueColor::Black()), game::Clue::Rank(3)] {
+        let score = clue(&line, 1, save);
+        assert!(!score.has_errors());
+        assert!(score > no_clue);
+    }
+}
+
+#[test]
+fn save_black_5_with_color() {
+    use game::Suit::*;
+    let line = variant_line(
+        "Black (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Black(), 5)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 4), (Green(), 4)],
+            [(Red(), 3), (Red(), 4), (Red(), 2), (Green(), 2)],
+        ],
+    );
+    let score = clue(&line, 1, game::Clue::Color(ClueColor::Black()));
+    assert!(!score.has_errors());
+}
+
+#[test]
+fn reversed_suit_starts_with_5() {
+    use game::Suit::*;
+    let mut line = variant_line(
+        "Reversed (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Green(), 4)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 5), (Green(), 5)],
+            [(Red(), 3), (Red(), 4), (Red(), 2), (Blue(), 2)],
+        ],
+    );
+    line.clued(
+        1,
+        0,
+        game::Clue::Color(ClueColor::Purple()),
+        PositionSet::create(4, 0b0001),
+    );
+    println!("line: {line:?}");
+    let focus = line.hands.slot(0, 0);
+    assert!(focus.play);
+    assert_eq!(focus.quantum.size(), 1);
+    assert!(focus.quantum.contains(&game::Card {
+        suit: Purple(),
+        rank: 5
+    }));
+}
+
+#[test]
+fn reversed_4_is_2_saved() {
+    use game::Suit::*;
+    let mut line = variant_line(
+        "Reversed (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Green(), 4)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 5), (Green(), 5)],
+            [(Red(), 3), (Red(), 4), (Red(), 2), (Blue(), 2)],
+        ],
+    );
+    // the purple 4 is the second card of the reversed suit: a 4 clue on chop saves it
+    line.clued(1, 0, game::Clue::Rank(4), PositionSet::create(4, 0b1000));
+    println!("line: {line:?}");
+    let chop = line.hands.slot(0, 3);
+    assert!(!chop.play);
+    assert!(chop.quantum.contains(&game::Card {
+        suit: Purple(),
+        rank: 4
+    }));
+}
+
+#[test]
+fn up_or_down_starts_with_1_or_5() {
+    use game::Suit::*;
+    let mut line = variant_line(
+        "Up or Down (5 Suits)",
+        [
+            [(Yellow(), 3), (Yellow(), 4), (Green(), 3), (Green(), 4)],
+            [(Blue(), 3), (Blue(), 4), (Yellow(), 2), (Green(), 2)],
+            [(Red(), 3), (Red(), 4), (Red(), 2), (Blue(), 2)],
+        ],
+    );
+    line.clued(
+        1,
+        0,
+        game::Clue::Color(ClueColor::Purple()),
+        PositionSet::create(4, 0b0001),
+    );
+    println!("line: {line:?}");
+    let focus = line.hands.slot(0, 0);
+    assert!(focus.play);
+    assert_eq!(focus.quantum.size(), 2);
+    for rank in [1, 5] {
+        assert!(focus.quantum.contains(&game::Card {
+            suit: Purple(),
+            rank
+        }));
+    }
+}
+
+#[test]
+fn empty_clue_gives_negative_information() {
+    let options = game::GameOptions {
+        empty_clues: true,
+        ..Default::default()
+    };
+    let mut line =
+        hyphenated::Line::with_logger(4, 0, Variant::no_variant(), options, get_logger());
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    hand!(line 1: [r 3, r 4, g 4, b 4]);
+    hand!(line 2: [y 3, y 3, y 4, y 4]);
+    hand!(line 3: [g 4, g 4, g 3, g 3]);
+
+    // bob has no 1s; the clue is only legal because of the option
+    assert!(line.clone().clue(1, game::Clue::Rank(1)).is_some());
+    let mut strict_line = hyphenated::Line::new(4, 0);
+    strict_line.own_drawn();
+    hand!(strict_line 1: [r 3, r 4, g 4, b 4]);
+    assert!(strict_line.clue(1, game::Clue::Rank(1)).is_none());
+
+    line.clued(1, 0, game::Clue::Rank(5), PositionSet::create(4, 0));
+    println!("line: {line:?}");
+    for pos in 0..4 {
+        let slot = line.hands.slot(0, pos);
+        assert!(!slot.clued);
+        assert!(slot.quantum.iter().all(|card| card.rank != 5));
+    }
+}
+
+#[test]
+fn legal_moves_from_own_perspective() {
+    let mut line = hyphenated::Line::new(4, 0);
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    hand!(line 1: [r 3, r 4, g 4, b 4]);
+    hand!(line 2: [y 3, y 3, y 4, y 4]);
+    hand!(line 3: [g 4, g 4, g 3, g 3]);
+
+    let moves = line.legal_moves(8);
+    assert!(!moves.iter().any(|m| matches!(m, game::Move::Discard(_))));
+    assert_eq!(
+        moves
+            .iter()
+            .filter(|m| matches!(m, game::Move::Play(_)))
+            .count(),
+        4
+    );
+    let clues: Vec<_> = moves
+        .iter()
+        .filter_map(|m| match m {
+            game::Move::Clue(1, clue) => Some(*clue),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        clues,
+        vec![
+            game::Clue::Color(game::ClueColor::Red()),
+            game::Clue::Color(game::ClueColor::Green()),
+            game::Clue::Color(game::ClueColor::Blue()),
+            game::Clue::Rank(3),
+            game::Clue::Rank(4),
+        ]
+    );
+    // cathy and donald have a single color and two ranks each
+    assert_eq!(moves.len(), 4 + 5 + 3 + 3);
+
+    let moves = line.legal_moves(0);
+    assert_eq!(moves.len(), 8);
+    assert!(!moves.iter().any(|m| matches!(m, game::Move::Clue(_, _))));
+}
+
+#[test]
+fn released_delays_explain_misplays_for_a_round() {
+    let mut line = hyphenated::Line::new(4, 0);
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    line.own_drawn();
+    hand!(line 1: [r 3, r 1, g 4, b 5]);
+    hand!(line 2: [y 3, y 1, y 4, y 4]);
+    hand!(line 3: [g 4, r 1, g 3, g 1]);
+    let r2 = game::Card {
+        rank: 2,
+        suit: game::Suit::Red(),
+    };
+    let discard_oldest = |line: &mut hyphenated::Line, player: u8| {
+        let pos = line.hands.hand_sizes[player as usize] - 1;
+        let card = line.hands.slot(player, pos).card;
+        line.discarded(player as usize, pos as usize, card);
+    };
+
+    line.hands.slot_mut(0, 0).delayed = 1;
+    discard_oldest(&mut line, 1);
+    assert!(line.misplay_belief(0, r2).delayed);
+
+    // released, but still within the round
+    line.hands.slot_mut(0, 0).delayed = 0;
+    discard_oldest(&mut line, 2);
+    discard_oldest(&mut line, 3);
+    discard_oldest(&mut line, 1);
+    assert!(line.misplay_belief(0, r2).delayed);
+
+    discard_oldest(&mut line, 2);
+    discard_oldest(&mut line, 3);
+    assert!(!line.misplay_belief(0, r2).delayed);
+}
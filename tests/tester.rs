@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 
-use raikan::game;
+use raikan::{Variant, game};
 
 pub struct InstructedPlayer {
     actions: VecDeque<game::Move>,
@@ -30,6 +30,12 @@ impl InstructedPlayer {
     }
 }
 
+impl Default for InstructedPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl std::fmt::Debug for InstructedPlayer {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -41,7 +47,18 @@ impl std::fmt::Debug for InstructedPlayer {
 }
 
 impl game::PlayerStrategy for InstructedPlayer {
-    fn init(&mut self, _num_players: u8, _own_index: u8) {}
+    fn name(&self) -> String {
+        "instructed".to_string()
+    }
+
+    fn init(
+        &mut self,
+        _num_players: u8,
+        _own_index: u8,
+        _variant: Variant,
+        _options: game::GameOptions,
+    ) {
+    }
     fn drawn(&mut self, _player: usize, _card: game::Card) {}
     fn own_drawn(&mut self) {}
     fn played(&mut self, _player: usize, _pos: usize, _card: game::Card, _successful: bool) {}
@@ -50,7 +67,7 @@ impl game::PlayerStrategy for InstructedPlayer {
     fn clued(&mut self, _who: usize, _whom: usize, _clue: game::Clue, _touched: game::PositionSet) {
     }
 
-    fn act(&mut self, _status: &game::GameStatus) -> game::Move {
+    fn act(&mut self, _view: &game::PlayerView) -> game::Move {
         self.actions.pop_front().unwrap_or_else(|| {
             self.default
                 .expect("Player should be given enough instructions or a default")